@@ -0,0 +1,316 @@
+use nom::multi::separated_list1;
+use nom::{branch::alt, bytes::complete::tag, IResult};
+
+use nom::character::complete::{multispace0, multispace1};
+
+#[derive(Debug)]
+struct Game {
+    game_number: u32,
+    rounds: Vec<Round>,
+}
+
+#[derive(Debug)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Debug)]
+struct Round {
+    red: u32,
+    green: u32,
+    blue: u32,
+}
+
+struct Draw {
+    count: u32,
+    color: Color,
+}
+
+/// Chi-squared critical value (1 degree of freedom) for a 95% likelihood-ratio interval.
+const CHI_SQUARED_95: f64 = 3.841;
+
+/// Cubes of each color considered when no limit is given on the command line.
+const DEFAULT_MAX_PER_COLOR: u32 = 40;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Bag {
+    red: u32,
+    green: u32,
+    blue: u32,
+}
+
+impl Bag {
+    fn total(&self) -> u32 {
+        self.red + self.green + self.blue
+    }
+}
+
+#[derive(Debug)]
+struct Estimate {
+    minimal: Bag,
+    most_likely: Bag,
+    log_likelihood: f64,
+    // Range of bag totals whose best log-likelihood is within the 95% likelihood-ratio bound
+    interval: (u32, u32),
+    // True when the most likely bag, or any bag inside the interval, has as many cubes of
+    // some color as the search allows, so a higher limit could change either
+    truncated: bool,
+}
+
+fn color(input: &str) -> IResult<&str, Color> {
+    let (input, color) = alt((tag("red"), tag("green"), tag("blue")))(input)?;
+
+    match color {
+        "red" => Ok((input, Color::Red)),
+        "green" => Ok((input, Color::Green)),
+        "blue" => Ok((input, Color::Blue)),
+        _ => {
+            panic!("Unknown color: {}", color)
+        }
+    }
+}
+
+fn draw(input: &str) -> IResult<&str, Draw> {
+    let (input, _) = multispace0(input)?;
+    let (input, count) = nom::character::complete::u32(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, color) = color(input)?;
+
+    Ok((input, Draw { count, color }))
+}
+
+fn round(input: &str) -> IResult<&str, Round> {
+    let (input, draws) = separated_list1(tag(","), draw)(input)?;
+
+    let (mut r, mut g, mut b) = (0, 0, 0);
+    for draw in draws {
+        match draw.color {
+            Color::Red => r += draw.count,
+            Color::Green => g += draw.count,
+            Color::Blue => b += draw.count,
+        }
+    }
+
+    Ok((
+        input,
+        Round {
+            red: r,
+            green: g,
+            blue: b,
+        },
+    ))
+}
+
+fn game_number(input: &str) -> IResult<&str, u32> {
+    let (input, _) = tag("Game")(input)?;
+    let (input, _) = multispace0(input)?;
+    let (input, game_number) = nom::character::complete::digit1(input)?;
+    let (input, _) = tag(":")(input)?;
+    let (input, _) = multispace0(input)?;
+    let gn = game_number.parse().unwrap();
+    Ok((input, gn))
+}
+
+fn game(input: &str) -> IResult<&str, Game> {
+    let (input, game_number) = game_number(input)?;
+    let (input, rounds) = separated_list1(tag(";"), round)(input)?;
+
+    Ok((
+        input,
+        Game {
+            game_number,
+            rounds,
+        },
+    ))
+}
+
+/// Table of ln(n!) for n in 0..=max, so binomial coefficients can be taken in log space.
+fn ln_factorials(max: u32) -> Vec<f64> {
+    let mut table = Vec::with_capacity(max as usize + 1);
+    let mut acc = 0.0;
+    table.push(acc);
+    for n in 1..=max {
+        acc += (n as f64).ln();
+        table.push(acc);
+    }
+    table
+}
+
+fn ln_choose(ln_fact: &[f64], n: u32, k: u32) -> f64 {
+    ln_fact[n as usize] - ln_fact[k as usize] - ln_fact[(n - k) as usize]
+}
+
+/// Log-likelihood of a game given a bag, treating each round as a draw without
+/// replacement (multivariate hypergeometric) and putting the cubes back between rounds.
+fn log_likelihood(game: &Game, bag: &Bag, ln_fact: &[f64]) -> f64 {
+    game.rounds
+        .iter()
+        .map(|round| {
+            let drawn = round.red + round.green + round.blue;
+            ln_choose(ln_fact, bag.red, round.red)
+                + ln_choose(ln_fact, bag.green, round.green)
+                + ln_choose(ln_fact, bag.blue, round.blue)
+                - ln_choose(ln_fact, bag.total(), drawn)
+        })
+        .sum()
+}
+
+fn minimal_bag(game: &Game) -> Bag {
+    let mut bag = Bag {
+        red: 0,
+        green: 0,
+        blue: 0,
+    };
+    for r in &game.rounds {
+        bag.red = bag.red.max(r.red);
+        bag.green = bag.green.max(r.green);
+        bag.blue = bag.blue.max(r.blue);
+    }
+    bag
+}
+
+/// Searches every bag between the minimal bag and `max_per_color` cubes of each color for
+/// the one that makes the game most likely. Ties go to the smallest bag.
+///
+/// The likelihood doesn't always peak at a finite bag. As a bag grows, drawing from it
+/// without replacement looks more and more like drawing with replacement, and when the
+/// likelihood of that limit is higher it just keeps increasing. The most likely bag then
+/// sits on `max_per_color` and moves with it, so such estimates are marked `truncated`.
+fn estimate(game: &Game, max_per_color: u32) -> Estimate {
+    let minimal = minimal_bag(game);
+    let limit = Bag {
+        red: max_per_color.max(minimal.red),
+        green: max_per_color.max(minimal.green),
+        blue: max_per_color.max(minimal.blue),
+    };
+    let ln_fact = ln_factorials(limit.total());
+
+    let mut most_likely = minimal;
+    let mut best = f64::NEG_INFINITY;
+    // Profile likelihood: the best log-likelihood seen for each bag total
+    let mut by_total = vec![f64::NEG_INFINITY; limit.total() as usize + 1];
+    // The best log-likelihood of any bag with as many cubes of some color as allowed
+    let mut at_limit = f64::NEG_INFINITY;
+
+    for red in minimal.red..=limit.red {
+        for green in minimal.green..=limit.green {
+            for blue in minimal.blue..=limit.blue {
+                let bag = Bag { red, green, blue };
+                let ll = log_likelihood(game, &bag, &ln_fact);
+                let total = bag.total() as usize;
+                if ll > by_total[total] {
+                    by_total[total] = ll;
+                }
+                if (red == limit.red || green == limit.green || blue == limit.blue) && ll > at_limit
+                {
+                    at_limit = ll;
+                }
+                if ll > best || (ll == best && bag.total() < most_likely.total()) {
+                    best = ll;
+                    most_likely = bag;
+                }
+            }
+        }
+    }
+
+    let cutoff = best - CHI_SQUARED_95 / 2.0;
+    let mut inside = (minimal.total()..=limit.total()).filter(|&t| by_total[t as usize] >= cutoff);
+    let low = inside.next().unwrap_or(most_likely.total());
+    let high = inside.next_back().unwrap_or(low);
+
+    Estimate {
+        minimal,
+        most_likely,
+        log_likelihood: best,
+        interval: (low, high),
+        // The most likely bag counts too, as its log-likelihood is above the cutoff
+        truncated: at_limit >= cutoff,
+    }
+}
+
+fn main() {
+    let max_per_color = std::env::args()
+        .nth(1)
+        .map(|arg| arg.parse().expect("max cubes per color must be a number"))
+        .unwrap_or(DEFAULT_MAX_PER_COLOR);
+
+    let input = include_str!("../../input.txt");
+    println!("game | minimal (r,g,b) | most likely (r,g,b) | total | log-likelihood | 95% total interval");
+    for line in input.lines() {
+        let (remaining, g) = game(line).unwrap();
+        assert_eq!(remaining, "");
+
+        let e = estimate(&g, max_per_color);
+        println!(
+            "{:4} | {:>15} | {:>19} | {:5} | {:14.4} | {}..={}{}",
+            g.game_number,
+            format!("{},{},{}", e.minimal.red, e.minimal.green, e.minimal.blue),
+            format!(
+                "{},{},{}",
+                e.most_likely.red, e.most_likely.green, e.most_likely.blue
+            ),
+            e.most_likely.total(),
+            e.log_likelihood,
+            e.interval.0,
+            e.interval.1,
+            if e.truncated { "+" } else { "" },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_bounds() {
+        let test = include_str!("../../test.txt");
+        for line in test.lines() {
+            let (_, g) = game(line).unwrap();
+            let e = estimate(&g, 25);
+            assert!(e.most_likely.red >= e.minimal.red);
+            assert!(e.most_likely.green >= e.minimal.green);
+            assert!(e.most_likely.blue >= e.minimal.blue);
+            assert!(e.interval.0 <= e.most_likely.total());
+            assert!(e.most_likely.total() <= e.interval.1);
+        }
+    }
+
+    #[test]
+    fn test_estimate_single_color() {
+        // Only red is ever seen, so any other cube makes the draws less likely
+        let (_, g) = game("Game 1: 2 red; 2 red").unwrap();
+        let e = estimate(&g, 10);
+        assert_eq!(
+            e.most_likely,
+            Bag {
+                red: 2,
+                green: 0,
+                blue: 0
+            }
+        );
+        assert_eq!(e.log_likelihood, 0.0);
+        // But any number of red cubes fits just as well, up to the limit
+        assert!(e.truncated);
+    }
+
+    #[test]
+    fn test_estimate_at_limit() {
+        // Two of a color at a time is likelier the more of both colors there are, so the
+        // most likely bag is as big as the search allows
+        let (_, g) = game("Game 1: 2 red; 2 blue").unwrap();
+        let e = estimate(&g, 10);
+        assert_eq!(
+            e.most_likely,
+            Bag {
+                red: 10,
+                green: 0,
+                blue: 10
+            }
+        );
+        assert!(e.truncated);
+        assert_eq!(estimate(&g, 20).most_likely.total(), 40);
+    }
+}
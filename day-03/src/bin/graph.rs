@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use day_03::graph::Graph;
+use day_03::parse_schematic;
+
+// Usage: graph [dot] [<symbol>=<weight>...]
+// Without `dot` this prints the connected components and the numbers that aren't part
+// numbers. With `dot` it prints a Graphviz graph instead, e.g. `graph dot '*=5' | dot -Tsvg`.
+// Edges to `*` default to a weight of 2, everything else to 1.
+fn main() {
+    let mut dot = false;
+    let mut weights = HashMap::from([('*', 2)]);
+    for arg in std::env::args().skip(1) {
+        if arg == "dot" {
+            dot = true;
+            continue;
+        }
+        let (symbol, weight) = arg
+            .rsplit_once('=')
+            .expect("weights look like <symbol>=<weight>");
+        let symbol = symbol.chars().next().expect("missing symbol");
+        weights.insert(symbol, weight.parse().expect("weight must be a number"));
+    }
+
+    let input = include_str!("../../input.txt");
+    let schematic = parse_schematic(input);
+    let graph = Graph::new(&schematic);
+
+    if dot {
+        print!(
+            "{}",
+            graph.to_dot(|symbol| *weights.get(&symbol).unwrap_or(&1))
+        );
+        return;
+    }
+
+    let components = graph.components();
+    println!("components: {}", components.len());
+    for component in &components {
+        let symbols: String = component
+            .symbols
+            .iter()
+            .map(|&s| schematic.symbols[s].value)
+            .collect();
+        let numbers: Vec<String> = component
+            .numbers
+            .iter()
+            .map(|&n| schematic.numbers[n].value.to_string())
+            .collect();
+        println!("  [{}] {}", symbols, numbers.join(" "));
+    }
+
+    let isolated: Vec<String> = graph
+        .isolated_numbers()
+        .iter()
+        .map(|&n| {
+            let number = &schematic.numbers[n];
+            format!("{}@{},{}", number.value, number.row, number.col_start)
+        })
+        .collect();
+    println!("not part numbers: {}", isolated.len());
+    for number in isolated {
+        println!("  {}", number);
+    }
+}
@@ -0,0 +1,19 @@
+use day_03::parse_schematic;
+use day_03::render::{to_html, to_terminal};
+
+// Usage: render [html]
+// Prints the schematic with part numbers in green, other numbers in red and gears in bold.
+// With `html` the same view is printed as a web page instead.
+fn main() {
+    let input = include_str!("../../input.txt");
+    let schematic = parse_schematic(input);
+
+    match std::env::args().nth(1).as_deref() {
+        Some("html") => print!("{}", to_html(input, &schematic)),
+        Some(other) => {
+            eprintln!("unknown output format: {}", other);
+            std::process::exit(1);
+        }
+        None => print!("{}", to_terminal(input, &schematic)),
+    }
+}
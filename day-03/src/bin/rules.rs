@@ -0,0 +1,25 @@
+use day_03::{parse_schematic, Rules};
+
+// Usage: rules <spec>...
+// where each spec is <symbol>=<count>[+]:<product|sum|max>, `any` can stand in for the
+// symbol, and `distinct` counts each number at most once. For example part 2 is `*=2:product`.
+fn main() {
+    let specs: Vec<String> = std::env::args().skip(1).collect();
+    let rules = match Rules::parse(&specs) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let input = include_str!("../../input.txt");
+    let schematic = parse_schematic(input);
+    match rules.apply(&schematic) {
+        Ok(total) => println!("total: {}", total),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
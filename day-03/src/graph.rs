@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+use crate::Schematic;
+
+/// Bipartite adjacency graph between the numbers and symbols of a schematic. Edges are
+/// `(number, symbol)` index pairs into `Schematic::numbers` and `Schematic::symbols`.
+pub struct Graph<'a> {
+    schematic: &'a Schematic,
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// A connected group of numbers and symbols, as indices into the schematic.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Component {
+    pub numbers: Vec<usize>,
+    pub symbols: Vec<usize>,
+}
+
+fn find(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+impl<'a> Graph<'a> {
+    pub fn new(schematic: &'a Schematic) -> Graph<'a> {
+        let mut edges = Vec::new();
+        for (s, symbol) in schematic.symbols.iter().enumerate() {
+            for n in schematic.adjacent_numbers(symbol) {
+                edges.push((n, s));
+            }
+        }
+        Graph { schematic, edges }
+    }
+
+    /// Numbers without any adjacent symbol, i.e. the ones that aren't part numbers.
+    pub fn isolated_numbers(&self) -> Vec<usize> {
+        let mut connected = vec![false; self.schematic.numbers.len()];
+        for &(n, _) in &self.edges {
+            connected[n] = true;
+        }
+        (0..connected.len()).filter(|&n| !connected[n]).collect()
+    }
+
+    /// Connected components of the graph, largest first. Isolated numbers are left out (see
+    /// [`Graph::isolated_numbers`]), but a symbol with no numbers around it is still its own
+    /// component.
+    pub fn components(&self) -> Vec<Component> {
+        // Union-find over numbers followed by symbols
+        let offset = self.schematic.numbers.len();
+        let mut parents: Vec<usize> = (0..offset + self.schematic.symbols.len()).collect();
+        for &(n, s) in &self.edges {
+            let a = find(&mut parents, n);
+            let b = find(&mut parents, offset + s);
+            parents[a] = b;
+        }
+
+        let mut by_root: Vec<Option<usize>> = vec![None; parents.len()];
+        let mut components: Vec<Component> = Vec::new();
+        let isolated = self.isolated_numbers();
+        for node in 0..parents.len() {
+            if node < offset && isolated.binary_search(&node).is_ok() {
+                continue;
+            }
+            let root = find(&mut parents, node);
+            let c = *by_root[root].get_or_insert_with(|| {
+                components.push(Component {
+                    numbers: Vec::new(),
+                    symbols: Vec::new(),
+                });
+                components.len() - 1
+            });
+            if node < offset {
+                components[c].numbers.push(node);
+            } else {
+                components[c].symbols.push(node - offset);
+            }
+        }
+
+        components.sort_by_key(|c| std::cmp::Reverse(c.numbers.len() + c.symbols.len()));
+        components
+    }
+
+    /// Graphviz DOT rendering. Each edge carries `weight(symbol)` as its weight and the symbol
+    /// as its label; isolated numbers are grouped into their own cluster.
+    pub fn to_dot(&self, weight: impl Fn(char) -> u32) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "graph schematic {{").unwrap();
+
+        let isolated = self.isolated_numbers();
+        for (n, number) in self.schematic.numbers.iter().enumerate() {
+            if isolated.binary_search(&n).is_err() {
+                writeln!(dot, "  n{} [label=\"{}\" shape=box];", n, number.value).unwrap();
+            }
+        }
+        for (s, symbol) in self.schematic.symbols.iter().enumerate() {
+            writeln!(
+                dot,
+                "  s{} [label=\"{}\" shape=circle];",
+                s,
+                symbol.value.escape_default()
+            )
+            .unwrap();
+        }
+        for &(n, s) in &self.edges {
+            let symbol = self.schematic.symbols[s].value;
+            writeln!(
+                dot,
+                "  n{} -- s{} [weight={} label=\"{}\"];",
+                n,
+                s,
+                weight(symbol),
+                symbol.escape_default()
+            )
+            .unwrap();
+        }
+
+        if !isolated.is_empty() {
+            writeln!(dot, "  subgraph cluster_isolated {{").unwrap();
+            writeln!(dot, "    label=\"not part numbers\";").unwrap();
+            for n in isolated {
+                writeln!(
+                    dot,
+                    "    n{} [label=\"{}\" shape=box color=red];",
+                    n, self.schematic.numbers[n].value
+                )
+                .unwrap();
+            }
+            writeln!(dot, "  }}").unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_schematic;
+
+    #[test]
+    fn test_components() {
+        let test = include_str!("../test1.txt");
+        let schematic = parse_schematic(test);
+        let graph = Graph::new(&schematic);
+
+        let isolated: Vec<u32> = graph
+            .isolated_numbers()
+            .iter()
+            .map(|&n| schematic.numbers[n].value)
+            .collect();
+        assert_eq!(isolated, vec![114, 58]);
+
+        let components = graph.components();
+        assert_eq!(components.len(), 6);
+        // The `*` between 755 and 598 and the `*` between 467 and 35 are the biggest
+        assert_eq!(components[0].numbers.len(), 2);
+        assert_eq!(components[1].numbers.len(), 2);
+        assert!(components[2..].iter().all(|c| c.numbers.len() == 1));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let test = include_str!("../test4.txt");
+        let schematic = parse_schematic(test);
+        let dot = Graph::new(&schematic).to_dot(|c| if c == '*' { 2 } else { 1 });
+        assert!(dot.contains("n0 -- s0 [weight=2 label=\"*\"];"));
+        assert!(dot.contains("n1 -- s0 [weight=2 label=\"*\"];"));
+        assert!(!dot.contains("cluster_isolated"));
+    }
+}
@@ -0,0 +1,378 @@
+pub mod graph;
+pub mod render;
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    index: NumberIndex,
+}
+
+pub struct Number {
+    pub value: u32,
+    pub row: usize,
+    pub col_start: usize,
+    pub col_end: usize,
+}
+
+pub struct Symbol {
+    pub value: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Numbers bucketed by row and sorted by column, so a symbol only has to look at the few
+/// numbers in the rows around it instead of every number in the schematic.
+struct NumberIndex {
+    rows: Vec<Vec<usize>>,
+}
+
+impl NumberIndex {
+    fn new(numbers: &[Number]) -> NumberIndex {
+        let height = numbers.iter().map(|n| n.row + 1).max().unwrap_or(0);
+        let mut rows = vec![Vec::new(); height];
+        for (i, number) in numbers.iter().enumerate() {
+            rows[number.row].push(i);
+        }
+        for row in rows.iter_mut() {
+            row.sort_by_key(|&i| numbers[i].col_start);
+        }
+        NumberIndex { rows }
+    }
+
+    /// Indices into `numbers` of every number touching `symbol`, diagonals included. Any
+    /// digit of the number counts, not just the first and last.
+    fn adjacent<'a>(
+        &'a self,
+        numbers: &'a [Number],
+        symbol: &Symbol,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let col = symbol.col;
+        (symbol.row.saturating_sub(1)..=symbol.row + 1)
+            .filter_map(|row| self.rows.get(row))
+            .flat_map(move |row| {
+                let start = row.partition_point(|&i| numbers[i].col_end + 1 < col);
+                row[start..]
+                    .iter()
+                    .copied()
+                    .take_while(move |&i| numbers[i].col_start <= col + 1)
+            })
+    }
+}
+
+impl Schematic {
+    /// Indices into `numbers` of every number touching `symbol`.
+    pub fn adjacent_numbers<'a>(&'a self, symbol: &Symbol) -> impl Iterator<Item = usize> + 'a {
+        self.index.adjacent(&self.numbers, symbol)
+    }
+}
+
+/// Turns the byte offsets of matches in `line` into character columns. Columns count
+/// characters, not bytes, but matches come in order, so each one only counts the characters
+/// since the last.
+fn columns(line: &str) -> impl FnMut(usize) -> usize + '_ {
+    let (mut byte, mut col) = (0, 0);
+    move |at| {
+        col += line[byte..at].chars().count();
+        byte = at;
+        col
+    }
+}
+
+pub fn parse_schematic(input: &str) -> Schematic {
+    let numbers_re = Regex::new(r"[0-9]+").unwrap();
+    let symbols_re = Regex::new(r"[^0-9.]").unwrap();
+
+    let mut numbers = Vec::new();
+    let mut symbols = Vec::new();
+
+    for (row, line) in input.lines().enumerate() {
+        let mut col = columns(line);
+        let numbers_it = numbers_re.find_iter(line);
+        for m in numbers_it {
+            let col_start = col(m.start());
+            let number = Number {
+                value: m.as_str().parse().unwrap(),
+                row,
+                col_start,
+                col_end: col_start + m.as_str().len() - 1,
+            };
+            numbers.push(number);
+        }
+
+        let mut col = columns(line);
+        let symbols_it = symbols_re.find_iter(line);
+        for m in symbols_it {
+            let symbol = Symbol {
+                value: m.as_str().chars().next().unwrap(),
+                row,
+                col: col(m.start()),
+            };
+            symbols.push(symbol);
+        }
+    }
+
+    let index = NumberIndex::new(&numbers);
+    Schematic {
+        numbers,
+        symbols,
+        index,
+    }
+}
+
+/// How many adjacent numbers a symbol needs before its rule fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Predicate {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Predicate {
+    fn matches(&self, count: usize) -> bool {
+        match self {
+            Predicate::Exactly(n) => count == *n,
+            Predicate::AtLeast(n) => count >= *n,
+        }
+    }
+}
+
+/// How the numbers around a matching symbol are combined into its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Aggregation {
+    /// The combined value, or `None` if it doesn't fit in a `u64`. No numbers at all
+    /// combine to 0, whatever the aggregation.
+    fn apply(&self, mut values: impl Iterator<Item = u64>) -> Option<u64> {
+        let Some(first) = values.next() else {
+            return Some(0);
+        };
+        match self {
+            Aggregation::Product => values.try_fold(first, u64::checked_mul),
+            Aggregation::Sum => values.try_fold(first, u64::checked_add),
+            Aggregation::Max => values.max().max(Some(first)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub predicate: Predicate,
+    pub aggregation: Aggregation,
+}
+
+/// A set of per-symbol rules. Every symbol whose rule matches its adjacent numbers adds its
+/// aggregated value to the total; symbols without a rule fall back to the `any` rule, if set.
+#[derive(Debug, Default)]
+pub struct Rules {
+    by_symbol: HashMap<char, Rule>,
+    any: Option<Rule>,
+    // When set, a number already used by an earlier matching symbol is left out of later ones
+    distinct: bool,
+}
+
+impl Rules {
+    pub fn new() -> Rules {
+        Rules::default()
+    }
+
+    pub fn symbol(mut self, symbol: char, rule: Rule) -> Rules {
+        self.by_symbol.insert(symbol, rule);
+        self
+    }
+
+    pub fn any(mut self, rule: Rule) -> Rules {
+        self.any = Some(rule);
+        self
+    }
+
+    pub fn distinct(mut self, distinct: bool) -> Rules {
+        self.distinct = distinct;
+        self
+    }
+
+    /// Sum of every number touching at least one symbol, each counted once.
+    pub fn part1() -> Rules {
+        Rules::new()
+            .any(Rule {
+                predicate: Predicate::AtLeast(1),
+                aggregation: Aggregation::Sum,
+            })
+            .distinct(true)
+    }
+
+    /// Sum of the products of every `*` touching exactly two numbers.
+    pub fn part2() -> Rules {
+        Rules::new().symbol(
+            '*',
+            Rule {
+                predicate: Predicate::Exactly(2),
+                aggregation: Aggregation::Product,
+            },
+        )
+    }
+
+    /// Parses rules from command line specs like `*=2:product`, `#=1+:sum` or `any=1+:max`.
+    /// A trailing `+` on the count means "at least". The spec `distinct` turns on
+    /// [`Rules::distinct`].
+    pub fn parse<S: AsRef<str>>(specs: &[S]) -> Result<Rules, String> {
+        let mut rules = Rules::new();
+        for spec in specs {
+            let spec = spec.as_ref();
+            if spec == "distinct" {
+                rules = rules.distinct(true);
+                continue;
+            }
+
+            let (symbol, rest) = spec
+                .rsplit_once('=')
+                .ok_or_else(|| format!("{}: expected <symbol>=<count>:<aggregation>", spec))?;
+            let (count, aggregation) = rest
+                .split_once(':')
+                .ok_or_else(|| format!("{}: expected <count>:<aggregation>", spec))?;
+
+            let predicate = match count.strip_suffix('+') {
+                Some(n) => Predicate::AtLeast(
+                    n.parse()
+                        .map_err(|_| format!("{}: bad count {}", spec, count))?,
+                ),
+                None => Predicate::Exactly(
+                    count
+                        .parse()
+                        .map_err(|_| format!("{}: bad count {}", spec, count))?,
+                ),
+            };
+            let aggregation = match aggregation {
+                "product" => Aggregation::Product,
+                "sum" => Aggregation::Sum,
+                "max" => Aggregation::Max,
+                _ => return Err(format!("{}: unknown aggregation {}", spec, aggregation)),
+            };
+            let rule = Rule {
+                predicate,
+                aggregation,
+            };
+
+            let mut chars = symbol.chars();
+            rules = match (chars.next(), chars.next()) {
+                _ if symbol == "any" => rules.any(rule),
+                (Some(c), None) if c != '.' && !c.is_ascii_digit() => rules.symbol(c, rule),
+                _ => return Err(format!("{}: bad symbol {}", spec, symbol)),
+            };
+        }
+        Ok(rules)
+    }
+
+    fn rule_for(&self, symbol: char) -> Option<&Rule> {
+        self.by_symbol.get(&symbol).or(self.any.as_ref())
+    }
+
+    /// The total over every matching symbol, or an error naming the symbol whose value, or
+    /// the total at that point, doesn't fit in a `u64`.
+    pub fn apply(&self, schematic: &Schematic) -> Result<u64, String> {
+        let mut used = vec![false; schematic.numbers.len()];
+        let mut total = 0;
+        for symbol in &schematic.symbols {
+            let Some(rule) = self.rule_for(symbol.value) else {
+                continue;
+            };
+
+            let adjacent: Vec<usize> = schematic
+                .adjacent_numbers(symbol)
+                .filter(|&i| !(self.distinct && used[i]))
+                .collect();
+            if !rule.predicate.matches(adjacent.len()) {
+                continue;
+            }
+
+            for &i in &adjacent {
+                used[i] = true;
+            }
+            total = rule
+                .aggregation
+                .apply(adjacent.iter().map(|&i| schematic.numbers[i].value as u64))
+                .and_then(|value| value.checked_add(total))
+                .ok_or_else(|| {
+                    format!(
+                        "{} at row {}, column {}: total overflows a u64",
+                        symbol.value,
+                        symbol.row + 1,
+                        symbol.col + 1
+                    )
+                })?;
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_schematic() {
+        let test = include_str!("../test1.txt");
+        let schematic = parse_schematic(test);
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(schematic.symbols.len(), 6);
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = Rules::parse(&["*=2:product", "#=1+:max", "any=0+:sum", "distinct"]).unwrap();
+        assert_eq!(rules.by_symbol[&'*'], Rules::part2().by_symbol[&'*']);
+        assert_eq!(
+            rules.by_symbol[&'#'],
+            Rule {
+                predicate: Predicate::AtLeast(1),
+                aggregation: Aggregation::Max,
+            }
+        );
+        assert!(rules.any.is_some());
+        assert!(rules.distinct);
+
+        assert!(Rules::parse(&["*=two:product"]).is_err());
+        assert!(Rules::parse(&["*=2:mean"]).is_err());
+        assert!(Rules::parse(&["**=2:sum"]).is_err());
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let test = include_str!("../test1.txt");
+        let schematic = parse_schematic(test);
+        // Largest neighbour of every symbol: 467, 633, 617, 592, 664, 755
+        let rules = Rules::parse(&["any=1+:max"]).unwrap();
+        assert_eq!(rules.apply(&schematic), Ok(3728));
+    }
+
+    #[test]
+    fn test_aggregation_edges() {
+        // A symbol with no neighbours adds nothing, even to a product
+        let schematic = parse_schematic(
+            "*....
+.....
+..12#",
+        );
+        let rules = Rules::parse(&["any=0+:product"]).unwrap();
+        assert_eq!(rules.apply(&schematic), Ok(12));
+        let rules = Rules::parse(&["*=0:product"]).unwrap();
+        assert_eq!(rules.apply(&schematic), Ok(0));
+
+        // 10^24 doesn't fit in a u64
+        let schematic = parse_schematic("1000000.1000000\n.......*.......\n1000000.1000000");
+        let rules = Rules::parse(&["*=4:product"]).unwrap();
+        assert_eq!(
+            rules.apply(&schematic),
+            Err("* at row 2, column 8: total overflows a u64".to_string())
+        );
+        let rules = Rules::parse(&["*=4:sum"]).unwrap();
+        assert_eq!(rules.apply(&schematic), Ok(4000000));
+    }
+}
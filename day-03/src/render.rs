@@ -0,0 +1,177 @@
+use colored::Colorize;
+
+use crate::graph::Graph;
+use crate::Schematic;
+
+/// How a single character of the schematic should be drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    Plain,
+    PartNumber,
+    NotPartNumber,
+    Gear,
+}
+
+impl Highlight {
+    fn css_class(&self) -> Option<&'static str> {
+        match self {
+            Highlight::Plain => None,
+            Highlight::PartNumber => Some("part"),
+            Highlight::NotPartNumber => Some("not-part"),
+            Highlight::Gear => Some("gear"),
+        }
+    }
+}
+
+/// Every character of `input` paired with its highlight, one `Vec` per line. `schematic`
+/// must have been parsed from `input`.
+pub fn classify(input: &str, schematic: &Schematic) -> Vec<Vec<(char, Highlight)>> {
+    let mut lines: Vec<Vec<(char, Highlight)>> = input
+        .lines()
+        .map(|line| line.chars().map(|c| (c, Highlight::Plain)).collect())
+        .collect();
+
+    let isolated = Graph::new(schematic).isolated_numbers();
+    for (n, number) in schematic.numbers.iter().enumerate() {
+        let highlight = if isolated.binary_search(&n).is_ok() {
+            Highlight::NotPartNumber
+        } else {
+            Highlight::PartNumber
+        };
+        for cell in &mut lines[number.row][number.col_start..=number.col_end] {
+            cell.1 = highlight;
+        }
+    }
+
+    for symbol in &schematic.symbols {
+        if symbol.value == '*' && schematic.adjacent_numbers(symbol).count() == 2 {
+            lines[symbol.row][symbol.col].1 = Highlight::Gear;
+        }
+    }
+
+    lines
+}
+
+/// Consecutive characters sharing a highlight, so each run only needs one escape or `<span>`.
+fn runs(line: &[(char, Highlight)]) -> Vec<(String, Highlight)> {
+    let mut runs: Vec<(String, Highlight)> = Vec::new();
+    for &(c, highlight) in line {
+        match runs.last_mut() {
+            Some((text, h)) if *h == highlight => text.push(c),
+            _ => runs.push((c.to_string(), highlight)),
+        }
+    }
+    runs
+}
+
+/// The schematic with ANSI colors: part numbers green, other numbers red and gears bold.
+pub fn to_terminal(input: &str, schematic: &Schematic) -> String {
+    let mut out = String::new();
+    for line in classify(input, schematic) {
+        for (text, highlight) in runs(&line) {
+            let text = match highlight {
+                Highlight::Plain => text.normal(),
+                Highlight::PartNumber => text.green(),
+                Highlight::NotPartNumber => text.red(),
+                Highlight::Gear => text.bold(),
+            };
+            out.push_str(&text.to_string());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The same view as [`to_terminal`] as a standalone HTML page.
+pub fn to_html(input: &str, schematic: &Schematic) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>Schematic</title>\n\
+         <style>\n\
+         .part { color: green; }\n\
+         .not-part { color: red; }\n\
+         .gear { font-weight: bold; }\n\
+         </style>\n\
+         </head>\n\
+         <body>\n\
+         <pre>\n",
+    );
+    for line in classify(input, schematic) {
+        for (text, highlight) in runs(&line) {
+            match highlight.css_class() {
+                Some(class) => {
+                    out.push_str(&format!(
+                        "<span class=\"{}\">{}</span>",
+                        class,
+                        escape_html(&text)
+                    ));
+                }
+                None => out.push_str(&escape_html(&text)),
+            }
+        }
+        out.push('\n');
+    }
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_schematic;
+
+    #[test]
+    fn test_classify() {
+        let test = include_str!("../test1.txt");
+        let schematic = parse_schematic(test);
+        let lines = classify(test, &schematic);
+
+        assert_eq!(lines[0][0], ('4', Highlight::PartNumber));
+        assert_eq!(lines[0][5], ('1', Highlight::NotPartNumber));
+        assert_eq!(lines[1][3], ('*', Highlight::Gear));
+        // Only 617 touches this `*`
+        assert_eq!(lines[4][3], ('*', Highlight::Plain));
+        assert_eq!(lines[3][6], ('#', Highlight::Plain));
+    }
+
+    #[test]
+    fn test_classify_multibyte() {
+        // é takes two bytes but one column
+        let test = "é12*34\n.....é";
+        let schematic = parse_schematic(test);
+        let lines = classify(test, &schematic);
+        assert_eq!(
+            lines[0],
+            vec![
+                ('é', Highlight::Plain),
+                ('1', Highlight::PartNumber),
+                ('2', Highlight::PartNumber),
+                ('*', Highlight::Gear),
+                ('3', Highlight::PartNumber),
+                ('4', Highlight::PartNumber),
+            ]
+        );
+        assert_eq!(schematic.symbols[2].col, 5);
+    }
+
+    #[test]
+    fn test_to_html() {
+        let test = include_str!("../test1.txt");
+        let schematic = parse_schematic(test);
+        let html = to_html(test, &schematic);
+
+        assert!(
+            html.contains("<span class=\"part\">467</span>..<span class=\"not-part\">114</span>..")
+        );
+        assert!(html.contains("...<span class=\"gear\">*</span>......"));
+    }
+}
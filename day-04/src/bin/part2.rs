@@ -1,102 +1,144 @@
-use std::{cell::RefCell, collections::HashSet};
+use std::io::{BufRead, BufReader};
 
-use nom::{
-    bytes::complete::tag,
-    character::complete::{self, line_ending, space0, space1},
-    multi::separated_list1,
-    sequence::separated_pair,
-    IResult,
-};
+use day_04::card;
+use day_04::cascade::{cascade_wrapped, Cascade, CascadeError, OutOfRangePolicy};
+use day_04::validate::Validator;
 
-struct Card {
-    copies: u32,
-    card_number: u32,
-    winning_numbers: HashSet<u32>,
-    numbers_present: HashSet<u32>,
-}
-
-fn numbers_separated_by_space(input: &str) -> IResult<&str, Vec<u32>> {
-    // Trim off possible leading space
-    let (input, _) = space0(input)?;
-    separated_list1(space1, complete::u32)(input)
-}
-
-// Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-fn card(input: &str) -> IResult<&str, RefCell<Card>> {
-    let (input, _) = tag("Card")(input)?;
-    let (input, _) = space1(input)?;
-    let (input, card_number) = complete::u32(input)?;
-
-    let (input, _) = tag(": ")(input)?;
-
-    let (input, (winning_numbers, numbers_present)) = separated_pair(
-        numbers_separated_by_space,
-        tag(" | "),
-        numbers_separated_by_space,
-    )(input)?;
-
-    Ok((
-        input,
-        RefCell::new(Card {
-            copies: 1,
-            card_number,
-            winning_numbers: winning_numbers.into_iter().collect(),
-            numbers_present: numbers_present.into_iter().collect(),
-        }),
-    ))
-}
-
-fn cards(input: &str) -> IResult<&str, Vec<RefCell<Card>>> {
-    let (input, cards) = separated_list1(line_ending, card)(input)?;
-    Ok((input, cards))
-}
+fn part2<R: BufRead>(reader: R, policy: OutOfRangePolicy) -> Result<u64, CascadeError> {
+    let mut cascade = Cascade::new();
+    let mut counts = Vec::new();
+    let mut validator = Validator::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| CascadeError::Io(e.kind()))?;
+        // Problems are reported as each card is read, before it's scored
+        for finding in validator.check(&line) {
+            eprintln!("{}", finding);
+        }
+        if line.is_empty() {
+            continue;
+        }
+        let (_, card) = card(&line).map_err(|_| CascadeError::Parse { line: i + 1 })?;
+        let count = card.matches();
 
-fn part2(input: &str) -> u32 {
-    let mut cards = cards(input).unwrap().1;
-    for (i, card) in cards.iter().enumerate() {
-        let count = card
-            .borrow()
-            .numbers_present
-            .intersection(&card.borrow().winning_numbers)
-            .count();
-        for j in i + 1..i + 1 + count {
-            cards[j].borrow_mut().copies += card.borrow().copies;
+        match policy {
+            OutOfRangePolicy::Wrap => counts.push((card.card_number, count)),
+            _ => {
+                cascade.push(card.card_number, count)?;
+            }
         }
     }
 
-    cards.iter().map(|card| card.borrow().copies).sum()
+    match policy {
+        OutOfRangePolicy::Wrap => cascade_wrapped(&counts),
+        _ => cascade.finish(policy),
+    }
 }
 
+// Usage: part2 [clamp|error|wrap] [cards file]
+// Without a file the bundled input is used. Files are streamed a line at a time.
 fn main() {
-    let input = include_str!("../../input.txt");
-    println!("part2: {}", part2(input));
+    let mut args = std::env::args().skip(1);
+    let policy = match args.next().as_deref() {
+        None | Some("clamp") => OutOfRangePolicy::Clamp,
+        Some("error") => OutOfRangePolicy::Error,
+        Some("wrap") => OutOfRangePolicy::Wrap,
+        Some(other) => panic!("Unknown policy: {}", other),
+    };
+
+    let result = match args.next() {
+        Some(path) => {
+            let file = std::fs::File::open(&path).expect("Couldn't open cards file");
+            part2(BufReader::new(file), policy)
+        }
+        None => {
+            let input = include_str!("../../input.txt");
+            part2(input.as_bytes(), policy)
+        }
+    };
+
+    match result {
+        Ok(total) => println!("part2: {}", total),
+        Err(e) => {
+            eprintln!("part2: {:?}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day_04::deck;
+
+    #[test]
+    fn test_part2() {
+        let test = include_str!("../../test.txt");
+        let count = part2(test.as_bytes(), OutOfRangePolicy::Clamp);
+        assert_eq!(count, Ok(30));
+    }
 
     #[test]
-    fn parse_card() {
-        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
-        let (_, card) = card(input).unwrap();
-        assert_eq!(card.borrow().card_number, 1);
-        assert_eq!(card.borrow().winning_numbers.len(), 5);
-        assert_eq!(card.borrow().numbers_present.len(), 8);
+    fn test_late_wins() {
+        // Every card wins more cards than are left after it
+        let input = deck(3, 4);
+        assert_eq!(
+            part2(input.as_bytes(), OutOfRangePolicy::Clamp),
+            Ok(1 + 2 + 4)
+        );
+        assert_eq!(
+            part2(input.as_bytes(), OutOfRangePolicy::Error),
+            Err(CascadeError::OutOfRange {
+                card_number: 1,
+                wins: 4
+            })
+        );
+
+        // Card 1 wins 2, 3, 1, 2 so the copies are 2, 3, 2. Card 2 wins 3, 1, 2, 3 giving
+        // 5, 6, 8, then card 3 wins 1, 2, 3, 1 giving 21, 14, 16.
+        assert_eq!(
+            part2(input.as_bytes(), OutOfRangePolicy::Wrap),
+            Ok(21 + 14 + 16)
+        );
     }
 
     #[test]
-    fn parse_cards() {
-        let input = include_str!("../../test.txt");
-        let (remaining, cards) = cards(input).unwrap();
-        assert_eq!(remaining, "");
-        assert_eq!(cards.len(), 6);
+    fn test_copies_overflow() {
+        // Card n ends up with 2^(n-1) copies, which no longer fits in a u64 at card 65
+        let input = deck(70, 70);
+        assert_eq!(
+            part2(input.as_bytes(), OutOfRangePolicy::Clamp),
+            Err(CascadeError::CopiesOverflow { card_number: 65 })
+        );
     }
 
     #[test]
-    fn test_part2() {
-        let test = include_str!("../../test.txt");
-        let count = part2(test);
-        assert_eq!(count, 30);
+    fn test_matches_whole_deck() {
+        // The old approach: every card in memory, adding copies to each card won
+        fn whole_deck(input: &str) -> u64 {
+            let counts: Vec<usize> = input
+                .lines()
+                .map(|line| {
+                    let (_, card) = card(line).unwrap();
+                    card.matches()
+                })
+                .collect();
+            let mut copies = vec![1u64; counts.len()];
+            for (i, count) in counts.iter().enumerate() {
+                for j in i + 1..i + 1 + count {
+                    copies[j] += copies[i];
+                }
+            }
+            copies.iter().sum()
+        }
+
+        let input = include_str!("../../input.txt");
+        assert_eq!(
+            part2(input.as_bytes(), OutOfRangePolicy::Clamp),
+            Ok(whole_deck(input))
+        );
+        assert_eq!(
+            part2(input.as_bytes(), OutOfRangePolicy::Error),
+            Ok(whole_deck(input))
+        );
     }
 }
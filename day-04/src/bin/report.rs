@@ -0,0 +1,28 @@
+use day_04::cards;
+use day_04::report::Report;
+
+// Usage: report [csv|json] [cards file]
+// Prints a per-card table (matches, points, copies) with a match count histogram and totals.
+// Without a file the bundled input is used.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let format = args.next().unwrap_or_else(|| "csv".to_string());
+    let input = match args.next() {
+        Some(path) => std::fs::read_to_string(path).expect("Couldn't read cards file"),
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let (_, cards) = cards(&input).unwrap();
+    let report = match Report::new(&cards) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("report: {:?}", e);
+            std::process::exit(1);
+        }
+    };
+    match format.as_str() {
+        "csv" => print!("{}", report.to_csv()),
+        "json" => print!("{}", report.to_json()),
+        other => panic!("Unknown format: {}", other),
+    }
+}
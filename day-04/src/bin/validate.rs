@@ -0,0 +1,20 @@
+use day_04::validate::validate;
+
+// Usage: validate [cards file]
+// Lists anything odd about the deck: card numbers out of order or repeated, numbers repeated
+// within a list, and lists of a different length than on the first card. Exits with 1 if
+// anything was found. Without a file the bundled input is checked.
+fn main() {
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path).expect("Couldn't read cards file"),
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let findings = validate(&input);
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    if !findings.is_empty() {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::VecDeque;
+
+/// What to do with copies won past the last card in the deck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutOfRangePolicy {
+    /// Drop them, as the puzzle says this never happens anyway
+    Clamp,
+    /// Stop with `CascadeError::OutOfRange`
+    Error,
+    /// Carry on from the start of the deck. Cards already scored keep the extra copies but
+    /// don't win anything more with them. This needs to know how long the deck is before
+    /// scoring, so the match count of every card is kept in memory.
+    Wrap,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CascadeError {
+    OutOfRange {
+        card_number: u32,
+        wins: usize,
+    },
+    CopiesOverflow {
+        card_number: u32,
+    },
+    /// A card's points, or the total so far, don't fit in a `u64`
+    PointsOverflow {
+        card_number: u32,
+    },
+    Parse {
+        line: usize,
+    },
+    Io(std::io::ErrorKind),
+}
+
+/// Scores cards one at a time, remembering only the copies owed to the next few cards.
+///
+/// Winning `count` cards adds a card's copies to a whole run of upcoming cards, so instead
+/// of touching each of them the run is stored as a difference array: the copies are gained
+/// at the first card of the run and lost again after the last. The ring buffer never needs
+/// more slots than the most matches on a single card, however long the deck is.
+#[derive(Debug, Default)]
+pub struct Cascade {
+    // gained[k] / lost[k] apply to the card `k` places after the next one to be pushed
+    gained: VecDeque<u64>,
+    lost: VecDeque<u64>,
+    // Copies of the next card won by earlier cards
+    running: u64,
+    position: usize,
+    total: u64,
+    // Cards that won furthest past their own position so far, as
+    // (card_number, wins, index of the last card won). Only needed for
+    // `OutOfRangePolicy::Error`, and only cards that might still run off the end are kept.
+    furthest: VecDeque<(u32, usize, usize)>,
+}
+
+impl Cascade {
+    pub fn new() -> Cascade {
+        Cascade::default()
+    }
+
+    /// Scores the next card given how many numbers it matched, returning its copies.
+    pub fn push(&mut self, card_number: u32, count: usize) -> Result<u64, CascadeError> {
+        let overflow = || CascadeError::CopiesOverflow { card_number };
+
+        self.running -= self.lost.pop_front().unwrap_or(0);
+        self.running = self
+            .running
+            .checked_add(self.gained.pop_front().unwrap_or(0))
+            .ok_or_else(overflow)?;
+        let copies = self.running.checked_add(1).ok_or_else(overflow)?;
+        self.total = self.total.checked_add(copies).ok_or_else(overflow)?;
+
+        if count > 0 {
+            // This card's own slot has been popped, so slot 0 is the first card won and slot
+            // `count` the first one after the run
+            if self.lost.len() <= count {
+                self.gained.resize(count + 1, 0);
+                self.lost.resize(count + 1, 0);
+            }
+            self.gained[0] = self.gained[0].checked_add(copies).ok_or_else(overflow)?;
+            self.lost[count] = self.lost[count].checked_add(copies).ok_or_else(overflow)?;
+
+            let reach = self.position + count;
+            if self.furthest.back().is_none_or(|&(_, _, r)| reach > r) {
+                self.furthest.push_back((card_number, count, reach));
+            }
+        }
+
+        self.position += 1;
+        while self
+            .furthest
+            .front()
+            .is_some_and(|&(_, _, reach)| reach < self.position)
+        {
+            self.furthest.pop_front();
+        }
+
+        Ok(copies)
+    }
+
+    /// Total copies once every card has been pushed. Copies still owed to cards past the end
+    /// are dropped, unless `policy` is `OutOfRangePolicy::Error`.
+    pub fn finish(self, policy: OutOfRangePolicy) -> Result<u64, CascadeError> {
+        match (policy, self.furthest.front()) {
+            (OutOfRangePolicy::Error, Some(&(card_number, wins, _))) => {
+                Err(CascadeError::OutOfRange { card_number, wins })
+            }
+            _ => Ok(self.total),
+        }
+    }
+}
+
+/// Wrapping copies back to the start of the deck needs the whole deck, so this scores a list
+/// of `(card_number, matches)` instead of streaming.
+pub fn cascade_wrapped(counts: &[(u32, usize)]) -> Result<u64, CascadeError> {
+    let mut copies = vec![1u64; counts.len()];
+    for (i, &(_, count)) in counts.iter().enumerate() {
+        let won = copies[i];
+        for j in i + 1..i + 1 + count {
+            let j = j % counts.len();
+            copies[j] = copies[j]
+                .checked_add(won)
+                .ok_or(CascadeError::CopiesOverflow {
+                    card_number: counts[j].0,
+                })?;
+        }
+    }
+
+    copies
+        .iter()
+        .zip(counts)
+        .try_fold(0u64, |total, (&copies, &(card_number, _))| {
+            total
+                .checked_add(copies)
+                .ok_or(CascadeError::CopiesOverflow { card_number })
+        })
+}
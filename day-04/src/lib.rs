@@ -0,0 +1,128 @@
+pub mod cascade;
+pub mod report;
+pub mod validate;
+
+use std::collections::HashSet;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, line_ending, space0, space1},
+    multi::separated_list1,
+    sequence::separated_pair,
+    IResult,
+};
+
+pub struct Card {
+    pub card_number: u32,
+    pub winning_numbers: HashSet<u32>,
+    pub numbers_present: HashSet<u32>,
+}
+
+impl Card {
+    /// How many of the numbers present are winning numbers.
+    pub fn matches(&self) -> usize {
+        self.numbers_present
+            .intersection(&self.winning_numbers)
+            .count()
+    }
+
+    /// Part 1 score: 1 point for the first match, doubled for every match after that. `None`
+    /// if that doesn't fit in a `u64`, from 65 matches on.
+    pub fn points(&self) -> Option<u64> {
+        match self.matches() {
+            0 => Some(0),
+            count => u32::try_from(count - 1)
+                .ok()
+                .and_then(|shift| 1u64.checked_shl(shift)),
+        }
+    }
+}
+
+fn numbers_separated_by_space(input: &str) -> IResult<&str, Vec<u32>> {
+    // Trim off possible leading space
+    let (input, _) = space0(input)?;
+    separated_list1(space1, complete::u32)(input)
+}
+
+/// A card exactly as written, before duplicate numbers are merged.
+pub struct RawCard {
+    pub card_number: u32,
+    pub winning_numbers: Vec<u32>,
+    pub numbers_present: Vec<u32>,
+}
+
+// Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+pub fn raw_card(input: &str) -> IResult<&str, RawCard> {
+    let (input, _) = tag("Card")(input)?;
+    let (input, _) = space1(input)?;
+    let (input, card_number) = complete::u32(input)?;
+
+    let (input, _) = tag(": ")(input)?;
+
+    let (input, (winning_numbers, numbers_present)) = separated_pair(
+        numbers_separated_by_space,
+        tag(" | "),
+        numbers_separated_by_space,
+    )(input)?;
+
+    Ok((
+        input,
+        RawCard {
+            card_number,
+            winning_numbers,
+            numbers_present,
+        },
+    ))
+}
+
+pub fn card(input: &str) -> IResult<&str, Card> {
+    let (input, raw) = raw_card(input)?;
+    Ok((
+        input,
+        Card {
+            card_number: raw.card_number,
+            winning_numbers: raw.winning_numbers.into_iter().collect(),
+            numbers_present: raw.numbers_present.into_iter().collect(),
+        },
+    ))
+}
+
+pub fn cards(input: &str) -> IResult<&str, Vec<Card>> {
+    let (input, cards) = separated_list1(line_ending, card)(input)?;
+    Ok((input, cards))
+}
+
+/// `len` cards, one per line, each matching `matches` numbers. Every card wins the next
+/// `matches` cards, which makes for decks with far too many copies or points.
+pub fn deck(len: u32, matches: u32) -> String {
+    let numbers: Vec<String> = (1..=matches).map(|n| n.to_string()).collect();
+    let numbers = numbers.join(" ");
+    (1..=len)
+        .map(|i| format!("Card {}: {} | {}", i, numbers, numbers))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_card() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
+        let (_, card) = card(input).unwrap();
+        assert_eq!(card.card_number, 1);
+        assert_eq!(card.winning_numbers.len(), 5);
+        assert_eq!(card.numbers_present.len(), 8);
+        assert_eq!(card.matches(), 4);
+        assert_eq!(card.points(), Some(8));
+    }
+
+    #[test]
+    fn parse_cards() {
+        let input = include_str!("../test.txt");
+        let (remaining, cards) = cards(input).unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(cards.len(), 6);
+    }
+}
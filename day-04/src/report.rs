@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::cascade::{Cascade, CascadeError, OutOfRangePolicy};
+use crate::Card;
+
+/// One line of the per-card table.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CardRow {
+    pub card_number: u32,
+    pub matches: usize,
+    pub points: u64,
+    /// Copies held at the end, counting the original
+    pub copies: u64,
+    /// Copies this card handed out to the cards after it
+    pub contributed: u64,
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub rows: Vec<CardRow>,
+    /// Number of cards (originals only) with each match count
+    pub histogram: BTreeMap<usize, usize>,
+    /// Card number and copies handed out for the card that handed out the most
+    pub top_contributor: Option<(u32, u64)>,
+    pub total_points: u64,
+    pub total_copies: u64,
+}
+
+impl Report {
+    /// Scores the whole deck. Copies won past the last card are dropped, as in part 2, and
+    /// the copies are checked the same way, so decks part 2 rejects are rejected here too.
+    pub fn new(cards: &[Card]) -> Result<Report, CascadeError> {
+        let matches: Vec<usize> = cards.iter().map(|card| card.matches()).collect();
+        let mut cascade = Cascade::new();
+        let mut rows = Vec::with_capacity(cards.len());
+        for (i, card) in cards.iter().enumerate() {
+            let card_number = card.card_number;
+            let copies = cascade.push(card_number, matches[i])?;
+            let won = matches[i].min(cards.len() - i - 1);
+            rows.push(CardRow {
+                card_number,
+                matches: matches[i],
+                points: 0,
+                copies,
+                contributed: copies
+                    .checked_mul(won as u64)
+                    .ok_or(CascadeError::CopiesOverflow { card_number })?,
+            });
+        }
+        let total_copies = cascade.finish(OutOfRangePolicy::Clamp)?;
+
+        // Points come second so a deck that overflows both reports its copies, like part 2
+        let mut total_points = 0u64;
+        for (row, card) in rows.iter_mut().zip(cards) {
+            let overflow = || CascadeError::PointsOverflow {
+                card_number: card.card_number,
+            };
+            row.points = card.points().ok_or_else(overflow)?;
+            total_points = total_points.checked_add(row.points).ok_or_else(overflow)?;
+        }
+
+        let mut histogram = BTreeMap::new();
+        for &count in &matches {
+            *histogram.entry(count).or_insert(0) += 1;
+        }
+
+        // Ties go to the earlier card
+        let top_contributor = rows
+            .iter()
+            .filter(|row| row.contributed > 0)
+            .fold(None, |best: Option<&CardRow>, row| match best {
+                Some(b) if b.contributed >= row.contributed => Some(b),
+                _ => Some(row),
+            })
+            .map(|row| (row.card_number, row.contributed));
+
+        Ok(Report {
+            total_points,
+            total_copies,
+            rows,
+            histogram,
+            top_contributor,
+        })
+    }
+
+    /// The per-card table, then the histogram and the summary as two more small tables,
+    /// separated by blank lines.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        writeln!(csv, "card,matches,points,copies,contributed").unwrap();
+        for row in &self.rows {
+            writeln!(
+                csv,
+                "{},{},{},{},{}",
+                row.card_number, row.matches, row.points, row.copies, row.contributed
+            )
+            .unwrap();
+        }
+
+        writeln!(csv).unwrap();
+        writeln!(csv, "matches,cards").unwrap();
+        for (matches, cards) in &self.histogram {
+            writeln!(csv, "{},{}", matches, cards).unwrap();
+        }
+
+        writeln!(csv).unwrap();
+        writeln!(csv, "statistic,value").unwrap();
+        writeln!(csv, "total_points,{}", self.total_points).unwrap();
+        writeln!(csv, "total_copies,{}", self.total_copies).unwrap();
+        if let Some((card_number, contributed)) = self.top_contributor {
+            writeln!(csv, "top_contributor,{}", card_number).unwrap();
+            writeln!(csv, "top_contributed,{}", contributed).unwrap();
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "    {{\"card\": {}, \"matches\": {}, \"points\": {}, \"copies\": {}, \"contributed\": {}}}",
+                    row.card_number, row.matches, row.points, row.copies, row.contributed
+                )
+            })
+            .collect();
+        let histogram: Vec<String> = self
+            .histogram
+            .iter()
+            .map(|(matches, cards)| format!("\"{}\": {}", matches, cards))
+            .collect();
+        let top_contributor = match self.top_contributor {
+            Some((card_number, contributed)) => format!(
+                "{{\"card\": {}, \"contributed\": {}}}",
+                card_number, contributed
+            ),
+            None => "null".to_string(),
+        };
+
+        format!(
+            "{{\n  \"cards\": [\n{}\n  ],\n  \"histogram\": {{{}}},\n  \"top_contributor\": {},\n  \"total_points\": {},\n  \"total_copies\": {}\n}}\n",
+            rows.join(",\n"),
+            histogram.join(", "),
+            top_contributor,
+            self.total_points,
+            self.total_copies
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cards, deck};
+
+    #[test]
+    fn test_report() {
+        let test = include_str!("../test.txt");
+        let cards = cards(test).unwrap().1;
+        let report = Report::new(&cards).unwrap();
+
+        assert_eq!(
+            report.rows[1],
+            CardRow {
+                card_number: 2,
+                matches: 2,
+                points: 2,
+                copies: 2,
+                contributed: 4,
+            }
+        );
+        assert_eq!(report.total_points, 13);
+        assert_eq!(report.total_copies, 30);
+        assert_eq!(
+            report.histogram,
+            BTreeMap::from([(0, 2), (1, 1), (2, 2), (4, 1)])
+        );
+        // Card 3 (4 copies, 2 wins) and card 4 (8 copies, 1 win) tie, so the earlier wins
+        assert_eq!(report.top_contributor, Some((3, 8)));
+
+        let csv = report.to_csv();
+        assert!(csv.starts_with("card,matches,points,copies,contributed\n1,4,8,1,4\n"));
+        let json = report.to_json();
+        assert!(json.contains("\"histogram\": {\"0\": 2, \"1\": 1, \"2\": 2, \"4\": 1}"));
+    }
+
+    #[test]
+    fn test_report_overflow() {
+        // Card n has 2^(n-1) copies, too many for a u64 at card 65, but card 62 already hands
+        // out 2^61 copies to each of the 8 cards after it
+        assert_eq!(
+            Report::new(&cards(&deck(70, 70)).unwrap().1).unwrap_err(),
+            CascadeError::CopiesOverflow { card_number: 62 }
+        );
+        // 64 cards' copies add up to exactly u64::MAX, but 70 matches is too many points
+        assert_eq!(
+            Report::new(&cards(&deck(64, 70)).unwrap().1).unwrap_err(),
+            CascadeError::PointsOverflow { card_number: 1 }
+        );
+        assert_eq!(
+            Report::new(&cards(&deck(1, 65)).unwrap().1).unwrap_err(),
+            CascadeError::PointsOverflow { card_number: 1 }
+        );
+        // 2^63 points each fits, but not two of them
+        assert_eq!(
+            Report::new(&cards(&deck(2, 64)).unwrap().1).unwrap_err(),
+            CascadeError::PointsOverflow { card_number: 2 }
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use crate::raw_card;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Problem {
+    Unparseable,
+    DuplicateCardNumber {
+        card_number: u32,
+    },
+    /// The card number isn't one more than the card before it
+    OutOfSequence {
+        card_number: u32,
+        expected: u32,
+    },
+    DuplicateWinningNumber {
+        card_number: u32,
+        number: u32,
+    },
+    DuplicatePresentNumber {
+        card_number: u32,
+        number: u32,
+    },
+    /// The lists are a different length than on the first card
+    ListLengths {
+        card_number: u32,
+        lengths: (usize, usize),
+        expected: (usize, usize),
+    },
+}
+
+/// A problem with the deck and the (1-based) line it was found on.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub line: usize,
+    pub problem: Problem,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.problem {
+            Problem::Unparseable => write!(f, "not a card"),
+            Problem::DuplicateCardNumber { card_number } => {
+                write!(f, "card {} appears more than once", card_number)
+            }
+            Problem::OutOfSequence {
+                card_number,
+                expected,
+            } => write!(
+                f,
+                "card {} where card {} was expected",
+                card_number, expected
+            ),
+            Problem::DuplicateWinningNumber {
+                card_number,
+                number,
+            } => write!(
+                f,
+                "card {} lists winning number {} more than once",
+                card_number, number
+            ),
+            Problem::DuplicatePresentNumber {
+                card_number,
+                number,
+            } => write!(
+                f,
+                "card {} lists number {} more than once",
+                card_number, number
+            ),
+            Problem::ListLengths {
+                card_number,
+                lengths,
+                expected,
+            } => write!(
+                f,
+                "card {} has {} winning and {} other numbers, the first card has {} and {}",
+                card_number, lengths.0, lengths.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+/// Numbers appearing more than once in `numbers`, in the order they're repeated.
+fn duplicates(numbers: &[u32]) -> Vec<u32> {
+    let mut seen = HashSet::new();
+    let mut reported = HashSet::new();
+    numbers
+        .iter()
+        .copied()
+        .filter(|&n| !seen.insert(n) && reported.insert(n))
+        .collect()
+}
+
+/// Checks a deck a line at a time, so it can run alongside the streaming part 2.
+///
+/// Card numbers seen so far are kept as ranges, so a well ordered deck only ever needs one.
+#[derive(Default)]
+pub struct Validator {
+    line: usize,
+    previous: Option<u32>,
+    seen: BTreeMap<u32, u32>,
+    lengths: Option<(usize, usize)>,
+}
+
+impl Validator {
+    pub fn new() -> Validator {
+        Validator::default()
+    }
+
+    fn seen(&self, card_number: u32) -> bool {
+        self.seen
+            .range(..=card_number)
+            .next_back()
+            .is_some_and(|(_, &end)| card_number <= end)
+    }
+
+    fn insert(&mut self, card_number: u32) {
+        let mut start = card_number;
+        let mut end = card_number;
+        if let Some((&s, &e)) = self.seen.range(..card_number).next_back() {
+            if e.checked_add(1) == Some(card_number) {
+                start = s;
+            }
+        }
+        if let Some(next) = card_number.checked_add(1) {
+            if let Some(e) = self.seen.remove(&next) {
+                end = e;
+            }
+        }
+        self.seen.insert(start, end);
+    }
+
+    /// Checks the next line of the deck. Blank lines are skipped but still counted.
+    pub fn check(&mut self, line: &str) -> Vec<Finding> {
+        self.line += 1;
+        if line.is_empty() {
+            return Vec::new();
+        }
+
+        let mut problems = Vec::new();
+        let card = match raw_card(line) {
+            Ok(("", card)) => card,
+            _ => {
+                return vec![Finding {
+                    line: self.line,
+                    problem: Problem::Unparseable,
+                }]
+            }
+        };
+        let card_number = card.card_number;
+
+        if self.seen(card_number) {
+            problems.push(Problem::DuplicateCardNumber { card_number });
+        } else {
+            if let Some(expected) = self.previous.and_then(|p| p.checked_add(1)) {
+                if card_number != expected {
+                    problems.push(Problem::OutOfSequence {
+                        card_number,
+                        expected,
+                    });
+                }
+            }
+            self.insert(card_number);
+        }
+        self.previous = Some(card_number);
+
+        for number in duplicates(&card.winning_numbers) {
+            problems.push(Problem::DuplicateWinningNumber {
+                card_number,
+                number,
+            });
+        }
+        for number in duplicates(&card.numbers_present) {
+            problems.push(Problem::DuplicatePresentNumber {
+                card_number,
+                number,
+            });
+        }
+
+        let lengths = (card.winning_numbers.len(), card.numbers_present.len());
+        match self.lengths {
+            None => self.lengths = Some(lengths),
+            Some(expected) if expected != lengths => problems.push(Problem::ListLengths {
+                card_number,
+                lengths,
+                expected,
+            }),
+            _ => {}
+        }
+
+        problems
+            .into_iter()
+            .map(|problem| Finding {
+                line: self.line,
+                problem,
+            })
+            .collect()
+    }
+}
+
+pub fn validate(input: &str) -> Vec<Finding> {
+    let mut validator = Validator::new();
+    input
+        .lines()
+        .flat_map(|line| validator.check(line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_deck() {
+        let test = include_str!("../test.txt");
+        assert_eq!(validate(test), vec![]);
+    }
+
+    #[test]
+    fn test_findings() {
+        // Card 3 on line 6 follows card 2 and joins up the ranges seen so far, so card 4 on
+        // line 7 is a repeat
+        let input = "Card 1: 1 2 3 | 4 5 6 7\n\
+                     Card 2: 1 2 2 | 4 5 6 6\n\
+                     Card 4: 1 2 3 | 4 5 6 7\n\
+                     Card 2: 1 2 3 | 4 5 6\n\
+                     Card five\n\
+                     Card 3: 1 2 3 | 4 5 6 7\n\
+                     Card 4: 1 2 3 | 4 5 6 7\n";
+        assert_eq!(
+            validate(input),
+            vec![
+                Finding {
+                    line: 2,
+                    problem: Problem::DuplicateWinningNumber {
+                        card_number: 2,
+                        number: 2
+                    }
+                },
+                Finding {
+                    line: 2,
+                    problem: Problem::DuplicatePresentNumber {
+                        card_number: 2,
+                        number: 6
+                    }
+                },
+                Finding {
+                    line: 3,
+                    problem: Problem::OutOfSequence {
+                        card_number: 4,
+                        expected: 3
+                    }
+                },
+                Finding {
+                    line: 4,
+                    problem: Problem::DuplicateCardNumber { card_number: 2 }
+                },
+                Finding {
+                    line: 4,
+                    problem: Problem::ListLengths {
+                        card_number: 2,
+                        lengths: (3, 3),
+                        expected: (3, 4)
+                    }
+                },
+                Finding {
+                    line: 5,
+                    problem: Problem::Unparseable
+                },
+                Finding {
+                    line: 7,
+                    problem: Problem::DuplicateCardNumber { card_number: 4 }
+                },
+            ]
+        );
+    }
+}
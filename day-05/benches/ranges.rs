@@ -0,0 +1,53 @@
+use std::time::{Duration, Instant};
+
+use day_05::{parse_input, Data};
+
+fn time(name: &str, data: &Data, solve: fn(&Data) -> u64, runs: u32) -> (u64, Duration) {
+    let start = Instant::now();
+    let mut answer = 0;
+    for _ in 0..runs {
+        answer = solve(data);
+    }
+    let each = start.elapsed() / runs;
+    println!("{:>24}: {:>12} in {:?}", name, answer, each);
+    (answer, each)
+}
+
+// Compares pushing every seed through the maps with moving whole ranges. The brute force run
+// on the real input takes minutes and every core, so it only happens with FULL=1:
+//
+//     FULL=1 cargo bench --bench ranges
+fn main() {
+    for (name, input) in [
+        ("test.txt", include_str!("../test.txt")),
+        ("input.txt", include_str!("../input.txt")),
+    ] {
+        let data = parse_input(input).unwrap();
+        let seeds: u64 = data
+            .seed_ranges()
+            .unwrap()
+            .iter()
+            .map(|r| r.end() - r.start() + 1)
+            .sum();
+        println!("{} ({} seeds)", name, seeds);
+
+        let (ranges, ranges_time) =
+            time("ranges", &data, |data| data.lowest_location().unwrap(), 100);
+        if name == "input.txt" && std::env::var("FULL").is_err() {
+            println!("{:>24}: skipped, set FULL=1 to run it", "brute force");
+            continue;
+        }
+        let (brute, brute_time) = time(
+            "brute force",
+            &data,
+            |data| data.lowest_location_brute_force().unwrap(),
+            1,
+        );
+        assert_eq!(ranges, brute);
+        println!(
+            "{:>24}: {:.0}x",
+            "speedup",
+            brute_time.as_secs_f64() / ranges_time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use day_05::check::{check, Severity};
+use day_05::parse_input;
+
+// Usage: check [almanac file]
+// Prints anything odd about each stage's maps, and exits with 1 if any of it is an error.
+fn main() {
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path).expect("Couldn't read almanac"),
+        None => include_str!("../../input.txt").to_string(),
+    };
+    let data = match parse_input(&input) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let findings = check(&data);
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    if findings
+        .iter()
+        .any(|finding| finding.issue.severity() == Severity::Error)
+    {
+        std::process::exit(1);
+    }
+}
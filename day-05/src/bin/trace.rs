@@ -0,0 +1,46 @@
+use day_05::parse_input;
+
+// Usage: trace <seed> [almanac file]
+// Prints the value after each stage, and which map line moved it there.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let seed: u64 = args
+        .next()
+        .expect("Usage: trace <seed> [almanac file]")
+        .parse()
+        .expect("Seed must be a number");
+    let input = match args.next() {
+        Some(path) => std::fs::read_to_string(path).expect("Couldn't read almanac"),
+        None => include_str!("../../input.txt").to_string(),
+    };
+    let data = match parse_input(&input) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let steps = data.trace(seed);
+    let width = steps
+        .iter()
+        .map(|step| step.category.len())
+        .chain(["category".len(), "seed".len()])
+        .max()
+        .unwrap();
+    println!("{:<width$}  {:>20}  matched", "category", "value");
+    println!("{:<width$}  {:>20}", "seed", seed);
+    for step in steps {
+        let matched = match step.map {
+            Some((i, map)) => format!(
+                "map {}: {} {} {}",
+                i + 1,
+                map.destination,
+                map.source,
+                map.range
+            ),
+            None => "identity".to_string(),
+        };
+        println!("{:<width$}  {:>20}  {}", step.category, step.value, matched);
+    }
+}
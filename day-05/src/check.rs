@@ -0,0 +1,282 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::{Data, Map, Stage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Something odd about the maps of one stage. Maps are numbered from 1 in the order they're
+/// listed under the stage's heading.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Issue {
+    /// Both maps cover some of the same source values, so which one applies depends on
+    /// their order
+    OverlappingSources { first: usize, second: usize },
+    /// Both maps send values to some of the same destinations
+    OverlappingDestinations { first: usize, second: usize },
+    /// The last source value doesn't fit in a `u64`, so the map is cut short (see
+    /// `Map::covered`)
+    SourceOverflow { map: usize },
+    /// The last destination doesn't fit in a `u64`, so the map is cut short
+    DestinationOverflow { map: usize },
+    /// Values between two maps that no map covers, so they pass through unchanged
+    IdentityGap { values: Range<u64> },
+    /// A map with a range of 0, which never applies
+    EmptyRange { map: usize },
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Issue::OverlappingSources { .. }
+            | Issue::SourceOverflow { .. }
+            | Issue::DestinationOverflow { .. } => Severity::Error,
+            Issue::OverlappingDestinations { .. }
+            | Issue::IdentityGap { .. }
+            | Issue::EmptyRange { .. } => Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Finding {
+    /// The stage's heading, like `seed-to-soil`
+    pub stage: String,
+    pub issue: Issue,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.issue.severity() {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}: ", severity, self.stage)?;
+        match &self.issue {
+            Issue::OverlappingSources { first, second } => {
+                write!(f, "maps {} and {} have overlapping sources", first, second)
+            }
+            Issue::OverlappingDestinations { first, second } => write!(
+                f,
+                "maps {} and {} have overlapping destinations",
+                first, second
+            ),
+            Issue::SourceOverflow { map } => write!(f, "map {} source end overflows", map),
+            Issue::DestinationOverflow { map } => {
+                write!(f, "map {} destination end overflows", map)
+            }
+            Issue::IdentityGap { values } => write!(
+                f,
+                "values {}..{} aren't mapped and stay the same",
+                values.start, values.end
+            ),
+            Issue::EmptyRange { map } => write!(f, "map {} has an empty range", map),
+        }
+    }
+}
+
+/// Pairs of maps whose `[start, start + range)` spans overlap, as 1-based map numbers. Ends
+/// are worked out in `u128` so overflowing maps are still compared sensibly.
+fn overlaps(maps: &[Map], start: impl Fn(&Map) -> u64) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..maps.len()).filter(|&i| maps[i].range > 0).collect();
+    order.sort_by_key(|&i| start(&maps[i]));
+
+    let mut pairs = Vec::new();
+    // Maps that haven't ended yet, and where they end
+    let mut open: Vec<(usize, u128)> = Vec::new();
+    for i in order {
+        let begin = start(&maps[i]) as u128;
+        let end = begin + maps[i].range as u128;
+        open.retain(|&(_, reach)| reach > begin);
+        for &(j, _) in &open {
+            pairs.push((i.min(j) + 1, i.max(j) + 1));
+        }
+        open.push((i, end));
+    }
+    pairs.sort();
+    pairs
+}
+
+/// Source values between the first and last map that none of the maps cover.
+fn gaps(maps: &[Map]) -> Vec<Range<u64>> {
+    let mut spans: Vec<(u128, u128)> = maps
+        .iter()
+        .filter(|map| map.range > 0)
+        .map(|map| (map.source as u128, map.source as u128 + map.range as u128))
+        .collect();
+    spans.sort();
+
+    let mut gaps = Vec::new();
+    let mut reach: Option<u128> = None;
+    for (begin, end) in spans {
+        if let Some(r) = reach {
+            if begin > r {
+                gaps.push(r as u64..begin as u64);
+            }
+        }
+        reach = Some(reach.map_or(end, |r| r.max(end)));
+    }
+    gaps
+}
+
+fn check_stage(stage: &Stage) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (i, map) in stage.maps.iter().enumerate() {
+        if map.range == 0 {
+            issues.push(Issue::EmptyRange { map: i + 1 });
+        }
+        if map.source as u128 + map.range as u128 > u64::MAX as u128 + 1 {
+            issues.push(Issue::SourceOverflow { map: i + 1 });
+        }
+        if map.destination as u128 + map.range as u128 > u64::MAX as u128 + 1 {
+            issues.push(Issue::DestinationOverflow { map: i + 1 });
+        }
+    }
+    for (first, second) in overlaps(&stage.maps, |map| map.source) {
+        issues.push(Issue::OverlappingSources { first, second });
+    }
+    for (first, second) in overlaps(&stage.maps, |map| map.destination) {
+        issues.push(Issue::OverlappingDestinations { first, second });
+    }
+    for values in gaps(&stage.maps) {
+        issues.push(Issue::IdentityGap { values });
+    }
+    issues
+}
+
+/// Every issue in every stage, in pipeline order.
+pub fn check(data: &Data) -> Vec<Finding> {
+    data.stages
+        .iter()
+        .flat_map(|stage| {
+            check_stage(stage).into_iter().map(|issue| Finding {
+                stage: format!("{}-to-{}", stage.from, stage.to),
+                issue,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    #[test]
+    fn test_check() {
+        // Every stage of the example covers one unbroken run of values
+        let test = include_str!("../test.txt");
+        assert_eq!(check(&parse_input(test).unwrap()), vec![]);
+
+        let input = "seeds: 1\n\n\
+                     seed-to-soil map:\n\
+                     60 10 5\n\
+                     62 20 5\n";
+        assert_eq!(
+            check(&parse_input(input).unwrap()),
+            vec![
+                Finding {
+                    stage: "seed-to-soil".to_string(),
+                    issue: Issue::OverlappingDestinations {
+                        first: 1,
+                        second: 2
+                    }
+                },
+                Finding {
+                    stage: "seed-to-soil".to_string(),
+                    issue: Issue::IdentityGap { values: 15..20 }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_errors() {
+        let stage = Stage {
+            from: "seed".to_string(),
+            to: "soil".to_string(),
+            maps: vec![
+                Map {
+                    destination: 0,
+                    source: 10,
+                    range: 10,
+                },
+                Map {
+                    destination: 100,
+                    source: 30,
+                    range: 0,
+                },
+                Map {
+                    destination: u64::MAX - 1,
+                    source: 15,
+                    range: 10,
+                },
+                Map {
+                    destination: 50,
+                    source: u64::MAX - 1,
+                    range: 2,
+                },
+            ],
+        };
+        assert_eq!(
+            check_stage(&stage),
+            vec![
+                Issue::EmptyRange { map: 2 },
+                Issue::DestinationOverflow { map: 3 },
+                Issue::OverlappingSources {
+                    first: 1,
+                    second: 3
+                },
+                Issue::IdentityGap {
+                    values: 25..u64::MAX - 1
+                },
+            ]
+        );
+
+        // Map 4 ends on u64::MAX exactly; one more value and it runs past
+        let stage = Stage {
+            from: "seed".to_string(),
+            to: "soil".to_string(),
+            maps: vec![Map {
+                destination: 50,
+                source: u64::MAX - 1,
+                range: 3,
+            }],
+        };
+        assert_eq!(check_stage(&stage), vec![Issue::SourceOverflow { map: 1 }]);
+
+        // B and C overlap each other inside A, which reaches past both
+        let stage = Stage {
+            from: "seed".to_string(),
+            to: "soil".to_string(),
+            maps: [(1000, 0, 100), (2000, 10, 10), (3000, 15, 15)]
+                .map(|(destination, source, range)| Map {
+                    destination,
+                    source,
+                    range,
+                })
+                .into(),
+        };
+        assert_eq!(
+            check_stage(&stage),
+            vec![
+                Issue::OverlappingSources {
+                    first: 1,
+                    second: 2
+                },
+                Issue::OverlappingSources {
+                    first: 1,
+                    second: 3
+                },
+                Issue::OverlappingSources {
+                    first: 2,
+                    second: 3
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,602 @@
+pub mod check;
+pub mod piecewise;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, alpha1, line_ending, multispace0},
+    multi::separated_list1,
+    sequence::pair,
+    IResult,
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use piecewise::Piecewise;
+
+#[derive(Debug)]
+pub struct Map {
+    pub destination: u64,
+    pub source: u64,
+    pub range: u64,
+}
+
+impl Map {
+    /// The source values this map moves. A map running past `u64::MAX` at either end, which
+    /// [`check`](crate::check) reports, is cut short so everything it moves still fits.
+    pub fn covered(&self) -> Option<RangeInclusive<u64>> {
+        let last = self.range.checked_sub(1)?;
+        let mut end = self.source.saturating_add(last);
+        if self.destination > self.source {
+            end = end.min(u64::MAX - (self.destination - self.source));
+        }
+        Some(self.source..=end).filter(|covered| !covered.is_empty())
+    }
+
+    pub fn apply(&self, value: u64) -> Option<u64> {
+        match self.covered() {
+            Some(covered) if covered.contains(&value) => {
+                Some(value - self.source + self.destination)
+            }
+            _ => None,
+        }
+    }
+
+    /// Splits `values` into the part this map moves, already moved to its destination, and
+    /// the (up to two) parts it leaves alone.
+    fn apply_range(
+        &self,
+        values: RangeInclusive<u64>,
+    ) -> (Option<RangeInclusive<u64>>, Vec<RangeInclusive<u64>>) {
+        let Some(covered) = self.covered() else {
+            return (None, vec![values]);
+        };
+        // Inclusive ends, so a range can run right up to u64::MAX on either side of the map
+        let start = *values.start().max(covered.start());
+        let end = *values.end().min(covered.end());
+        if start > end {
+            return (None, vec![values]);
+        }
+
+        let mut untouched = Vec::new();
+        if *values.start() < start {
+            untouched.push(*values.start()..=start - 1);
+        }
+        if end < *values.end() {
+            untouched.push(end + 1..=*values.end());
+        }
+        let moved = start - self.source + self.destination..=end - self.source + self.destination;
+        (Some(moved), untouched)
+    }
+}
+
+/// One `X-to-Y map:` section of the almanac.
+#[derive(Debug)]
+pub struct Stage {
+    pub from: String,
+    pub to: String,
+    pub maps: Vec<Map>,
+}
+
+/// One stage of a [`Data::trace`].
+#[derive(Debug)]
+pub struct Step<'a> {
+    /// The category the stage maps to, like `soil`
+    pub category: &'a str,
+    pub value: u64,
+    /// The map that moved the value and where it's listed in its stage (from 0), or `None`
+    /// if no map covered the value and it stayed put
+    pub map: Option<(usize, &'a Map)>,
+}
+
+#[derive(Debug)]
+pub struct Data {
+    pub seeds: Vec<u64>,
+    /// Chained so that the first stage starts at `seed` and each one starts where the one
+    /// before it ends.
+    pub stages: Vec<Stage>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AlmanacError {
+    /// The text didn't parse; holds the line it stopped on
+    Parse(String),
+    /// More than one section maps from the same category
+    DuplicateSource(String),
+    /// Following the sections from this category leads back to it
+    Cycle(String),
+    /// The chain from `seed` stops at `end`, but the section mapping from `unused` is
+    /// never reached
+    MissingLink { end: String, unused: String },
+    /// Part 2 reads the seeds in pairs, but this last one has no length to go with it
+    UnpairedSeed(u64),
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::Parse(line) => write!(f, "couldn't parse almanac at: {}", line),
+            AlmanacError::DuplicateSource(category) => {
+                write!(f, "more than one map from {}", category)
+            }
+            AlmanacError::Cycle(category) => write!(f, "maps from {} lead back to it", category),
+            AlmanacError::MissingLink { end, unused } => write!(
+                f,
+                "nothing maps from {} on, so the map from {} is never used",
+                end, unused
+            ),
+            AlmanacError::UnpairedSeed(seed) => {
+                write!(f, "seed {} has no length to go with it", seed)
+            }
+        }
+    }
+}
+
+// 50 98 2
+fn map(input: &str) -> IResult<&str, Map> {
+    let line = input;
+    let (input, numbers) = separated_list1(tag(" "), complete::u64)(input)?;
+    if numbers.len() != 3 {
+        // A failure rather than an error, so the whole parse stops at this line instead of
+        // ending the section before it
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            line,
+            nom::error::ErrorKind::Count,
+        )));
+    }
+    Ok((
+        input,
+        Map {
+            destination: numbers[0],
+            source: numbers[1],
+            range: numbers[2],
+        },
+    ))
+}
+
+// seed-to-soil map:
+// 50 98 2
+fn stage(input: &str) -> IResult<&str, Stage> {
+    let (input, from) = alpha1(input)?;
+    let (input, _) = tag("-to-")(input)?;
+    let (input, to) = alpha1(input)?;
+    let (input, _) = tag(" map:")(input)?;
+    let (input, _) = line_ending(input)?;
+    let (input, maps) = separated_list1(line_ending, map)(input)?;
+    Ok((
+        input,
+        Stage {
+            from: from.to_string(),
+            to: to.to_string(),
+            maps,
+        },
+    ))
+}
+
+fn sections(input: &str) -> IResult<&str, (Vec<u64>, Vec<Stage>)> {
+    let (input, _) = tag("seeds: ")(input)?;
+    let (input, seeds) = separated_list1(tag(" "), complete::u64)(input)?;
+    let (input, _) = line_ending(input)?;
+    let (input, _) = line_ending(input)?;
+    let (input, stages) = separated_list1(pair(line_ending, line_ending), stage)(input)?;
+    let (input, _) = multispace0(input)?;
+    Ok((input, (seeds, stages)))
+}
+
+/// Orders `stages` into a pipeline starting at `seed`, checking every stage is used exactly
+/// once along the way.
+fn chain(stages: Vec<Stage>) -> Result<Vec<Stage>, AlmanacError> {
+    let mut by_from: HashMap<String, Stage> = HashMap::new();
+    for stage in stages {
+        if by_from.contains_key(&stage.from) {
+            return Err(AlmanacError::DuplicateSource(stage.from));
+        }
+        by_from.insert(stage.from.clone(), stage);
+    }
+
+    // Every category has at most one way out, so following them from anywhere either stops
+    // or comes back round
+    for start in by_from.keys() {
+        let mut category = start;
+        let mut visited = HashSet::new();
+        while let Some(stage) = by_from.get(category) {
+            if !visited.insert(category) {
+                return Err(AlmanacError::Cycle(category.clone()));
+            }
+            category = &stage.to;
+        }
+    }
+
+    let mut chained = Vec::new();
+    let mut category = "seed".to_string();
+    while let Some(stage) = by_from.remove(&category) {
+        category = stage.to.clone();
+        chained.push(stage);
+    }
+
+    match by_from.into_keys().min() {
+        Some(unused) => Err(AlmanacError::MissingLink {
+            end: category,
+            unused,
+        }),
+        None => Ok(chained),
+    }
+}
+
+/// Parses an almanac with any number of `X-to-Y map:` sections, in any order, as long as
+/// they chain together from `seed`.
+pub fn parse_input(input: &str) -> Result<Data, AlmanacError> {
+    let (rest, (seeds, stages)) = sections(input).map_err(|e| {
+        let at = match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+            nom::Err::Incomplete(_) => "",
+        };
+        AlmanacError::Parse(at.lines().next().unwrap_or("").to_string())
+    })?;
+    if !rest.is_empty() {
+        return Err(AlmanacError::Parse(
+            rest.lines().next().unwrap_or("").to_string(),
+        ));
+    }
+
+    Ok(Data {
+        seeds,
+        stages: chain(stages)?,
+    })
+}
+
+/// The index of the first map covering `value`, and where it moves it to.
+pub fn find_map(value: u64, maps: &[Map]) -> Option<(usize, u64)> {
+    maps.iter()
+        .enumerate()
+        .find_map(|(i, map)| map.apply(value).map(|result| (i, result)))
+}
+
+pub fn find_map_value(value: u64, maps: &[Map]) -> Option<u64> {
+    find_map(value, maps).map(|(_, result)| result)
+}
+
+/// Moves whole ranges of values through one stage. Like [`find_map_value`] the first map
+/// covering a value wins, and values no map covers stay where they are.
+pub fn map_ranges(ranges: Vec<RangeInclusive<u64>>, maps: &[Map]) -> Vec<RangeInclusive<u64>> {
+    let mut moved = Vec::new();
+    let mut remaining = ranges;
+    for map in maps {
+        let mut untouched = Vec::new();
+        for range in remaining {
+            let (m, u) = map.apply_range(range);
+            moved.extend(m);
+            untouched.extend(u);
+        }
+        remaining = untouched;
+    }
+    moved.extend(remaining);
+    moved
+}
+
+impl Data {
+    /// Part 2 reads the seeds as `start length` pairs. Pairs with a length of 0 are left out,
+    /// and like [`Map::covered`] a range running past `u64::MAX` is cut short there.
+    pub fn seed_ranges(&self) -> Result<Vec<RangeInclusive<u64>>, AlmanacError> {
+        let pairs = self.seeds.chunks_exact(2);
+        if let [seed] = pairs.remainder() {
+            return Err(AlmanacError::UnpairedSeed(*seed));
+        }
+        Ok(pairs
+            .filter(|pair| pair[1] > 0)
+            .map(|pair| pair[0]..=pair[0].saturating_add(pair[1] - 1))
+            .collect())
+    }
+
+    pub fn location(&self, seed: u64) -> u64 {
+        self.stages.iter().fold(seed, |value, stage| {
+            find_map_value(value, &stage.maps).unwrap_or(value)
+        })
+    }
+
+    /// Where `seed` ends up after each stage, in pipeline order.
+    pub fn trace(&self, seed: u64) -> Vec<Step<'_>> {
+        let mut value = seed;
+        self.stages
+            .iter()
+            .map(|stage| {
+                let found = find_map(value, &stage.maps);
+                value = found.map_or(value, |(_, result)| result);
+                Step {
+                    category: &stage.to,
+                    value,
+                    map: found.map(|(i, _)| (i, &stage.maps[i])),
+                }
+            })
+            .collect()
+    }
+
+    /// Every stage composed into a single function from seed to location.
+    pub fn seed_to_location(&self) -> Piecewise {
+        self.stages.iter().fold(Piecewise::identity(), |f, stage| {
+            f.then(&Piecewise::from_maps(&stage.maps))
+        })
+    }
+
+    /// Every seed, listed in the almanac or not, whose location is below `limit`.
+    pub fn seeds_below(&self, limit: u64) -> Vec<RangeInclusive<u64>> {
+        match limit {
+            0 => Vec::new(),
+            _ => self.seed_to_location().preimage(0..=limit - 1),
+        }
+    }
+
+    /// Lowest location for any seed in the seed ranges, by pushing every single seed through
+    /// every stage. Slow, but obviously right.
+    pub fn lowest_location_brute_force(&self) -> Result<u64, AlmanacError> {
+        Ok(self
+            .seed_ranges()?
+            .into_iter()
+            .map(|range| {
+                range
+                    .into_par_iter()
+                    .map(|seed| self.location(seed))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .min()
+            .unwrap_or(u64::MAX))
+    }
+
+    /// Lowest location for any seed in the seed ranges, by splitting the ranges wherever a
+    /// map starts or ends and moving each piece as a whole.
+    pub fn lowest_location(&self) -> Result<u64, AlmanacError> {
+        let ranges = self
+            .stages
+            .iter()
+            .fold(self.seed_ranges()?, |ranges, stage| {
+                map_ranges(ranges, &stage.maps)
+            });
+        Ok(ranges
+            .iter()
+            .map(|range| *range.start())
+            .min()
+            .unwrap_or(u64::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_range() {
+        let map = Map {
+            destination: 50,
+            source: 98,
+            range: 2,
+        };
+        assert_eq!(
+            map.apply_range(90..=109),
+            (Some(50..=51), vec![90..=97, 100..=109])
+        );
+        assert_eq!(map.apply_range(98..=98), (Some(50..=50), vec![]));
+        assert_eq!(map.apply_range(0..=97).0, None);
+
+        // The destination ends exactly at u64::MAX
+        let map = Map {
+            destination: u64::MAX - 1,
+            source: 0,
+            range: 2,
+        };
+        assert_eq!(
+            map.apply_range(0..=4),
+            (Some(u64::MAX - 1..=u64::MAX), vec![2..=4])
+        );
+    }
+
+    // Small almanacs with overlapping maps, from a fixed seed so failures are repeatable
+    fn random_almanac(seed: u64) -> Data {
+        let mut state = seed;
+        let mut next = |below: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % below
+        };
+        let categories = [
+            "seed",
+            "soil",
+            "fertilizer",
+            "water",
+            "light",
+            "temperature",
+            "humidity",
+            "location",
+        ];
+        let stages = categories
+            .windows(2)
+            .map(|names| Stage {
+                from: names[0].to_string(),
+                to: names[1].to_string(),
+                maps: (0..1 + next(5))
+                    .map(|_| Map {
+                        destination: next(200),
+                        source: next(200),
+                        range: 1 + next(40),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Data {
+            seeds: (0..2).flat_map(|_| [next(200), next(50)]).collect(),
+            stages,
+        }
+    }
+
+    #[test]
+    fn test_ranges_match_brute_force() {
+        let test = include_str!("../test.txt");
+        let data = parse_input(test).unwrap();
+        assert_eq!(data.lowest_location(), data.lowest_location_brute_force());
+
+        // Seeds 0 and 1 land on the last two values there are
+        let top = "seeds: 0 5\n\nseed-to-soil map:\n18446744073709551614 0 2\n";
+        let data = parse_input(top).unwrap();
+        assert_eq!(data.lowest_location(), Ok(2));
+        assert_eq!(data.lowest_location(), data.lowest_location_brute_force());
+
+        // The seed range runs past u64::MAX, so it stops there
+        let past =
+            "seeds: 18446744073709551610 10\n\nseed-to-soil map:\n7 18446744073709551615 1\n";
+        let data = parse_input(past).unwrap();
+        assert_eq!(data.seed_ranges(), Ok(vec![u64::MAX - 5..=u64::MAX]));
+        assert_eq!(data.lowest_location(), Ok(7));
+        assert_eq!(data.lowest_location(), data.lowest_location_brute_force());
+
+        let odd = "seeds: 1 2 3\n\nseed-to-soil map:\n0 0 1\n";
+        let data = parse_input(odd).unwrap();
+        assert_eq!(data.lowest_location(), Err(AlmanacError::UnpairedSeed(3)));
+
+        for seed in 0..200 {
+            let data = random_almanac(seed);
+            assert_eq!(
+                data.lowest_location(),
+                data.lowest_location_brute_force(),
+                "almanac {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let test = include_str!("../test.txt");
+        let data = parse_input(test).unwrap();
+        let steps: Vec<(&str, u64, Option<usize>)> = data
+            .trace(79)
+            .iter()
+            .map(|step| (step.category, step.value, step.map.map(|(i, _)| i)))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("soil", 81, Some(1)),
+                ("fertilizer", 81, None),
+                ("water", 81, None),
+                ("light", 74, Some(1)),
+                ("temperature", 78, Some(2)),
+                ("humidity", 78, None),
+                ("location", 82, Some(0)),
+            ]
+        );
+        assert_eq!(data.trace(79).last().unwrap().value, data.location(79));
+    }
+
+    #[test]
+    fn test_parse_any_order() {
+        // The test almanac with its sections reversed and an extra stage on the end
+        let test = include_str!("../test.txt");
+        let mut sections: Vec<&str> = test.trim_end().split("\n\n").collect();
+        sections[1..].reverse();
+        let input = sections.join("\n\n") + "\n\nlocation-to-plot map:\n100 30 10\n";
+
+        let data = parse_input(&input).unwrap();
+        let names: Vec<&str> = data.stages.iter().map(|s| s.to.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location",
+                "plot"
+            ]
+        );
+        // Seed 13 lands on location 35, inside the new map, and seed 82 on 46, outside it
+        assert_eq!(data.location(13), 105);
+        assert_eq!(data.location(82), 46);
+    }
+
+    #[test]
+    fn test_chain_errors() {
+        let missing = "seeds: 1 2\n\n\
+                       seed-to-soil map:\n0 0 1\n\n\
+                       water-to-light map:\n0 0 1\n";
+        assert_eq!(
+            parse_input(missing).unwrap_err(),
+            AlmanacError::MissingLink {
+                end: "soil".to_string(),
+                unused: "water".to_string()
+            }
+        );
+
+        let cycle = "seeds: 1 2\n\n\
+                     seed-to-soil map:\n0 0 1\n\n\
+                     soil-to-water map:\n0 0 1\n\n\
+                     water-to-soil map:\n0 0 1\n";
+        assert!(matches!(
+            parse_input(cycle).unwrap_err(),
+            AlmanacError::Cycle(_)
+        ));
+
+        let duplicate = "seeds: 1 2\n\n\
+                         seed-to-soil map:\n0 0 1\n\n\
+                         seed-to-water map:\n0 0 1\n";
+        assert_eq!(
+            parse_input(duplicate).unwrap_err(),
+            AlmanacError::DuplicateSource("seed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let short = "seeds: 1 2\n\nseed-to-soil map:\n0 0 1\n5 6\n";
+        assert_eq!(
+            parse_input(short).unwrap_err(),
+            AlmanacError::Parse("5 6".to_string())
+        );
+        let long = "seeds: 1 2\n\nseed-to-soil map:\n0 0 1 2\n";
+        assert_eq!(
+            parse_input(long).unwrap_err(),
+            AlmanacError::Parse("0 0 1 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_composition_matches_stages() {
+        let test = include_str!("../test.txt");
+        let mut almanacs = vec![parse_input(test).unwrap()];
+        almanacs.extend((0..100).map(random_almanac));
+
+        for data in almanacs {
+            let f = data.seed_to_location();
+            let mut seeds_at: HashMap<u64, Vec<u64>> = HashMap::new();
+            for seed in 0..300 {
+                let location = data.stages.iter().fold(seed, |value, stage| {
+                    find_map_value(value, &stage.maps).unwrap_or(value)
+                });
+                assert_eq!(f.apply(seed), location);
+                seeds_at.entry(location).or_default().push(seed);
+            }
+
+            for (location, seeds) in seeds_at {
+                let inverse: Vec<u64> = f
+                    .inverse(location)
+                    .into_iter()
+                    .filter(|&seed| seed < 300)
+                    .collect();
+                assert_eq!(inverse, seeds);
+            }
+
+            let below: Vec<u64> = data
+                .seeds_below(50)
+                .into_iter()
+                .flatten()
+                .take_while(|&seed| seed < 300)
+                .collect();
+            let expected: Vec<u64> = (0..300).filter(|&seed| f.apply(seed) < 50).collect();
+            assert_eq!(below, expected);
+        }
+    }
+}
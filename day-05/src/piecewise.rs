@@ -0,0 +1,238 @@
+use std::ops::RangeInclusive;
+
+use crate::Map;
+
+/// A piece of a [`Piecewise`] function: every value from `start` up to the next piece's
+/// start (or `u64::MAX` for the last piece) is moved by `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub start: u64,
+    pub offset: i128,
+}
+
+/// A function on `u64` that shifts each of a few ranges by a constant, like one almanac stage
+/// or a whole chain of them composed together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piecewise {
+    // Sorted by start, the first starting at 0, and no two neighbours with the same offset
+    pieces: Vec<Piece>,
+}
+
+fn shift(value: u64, offset: i128) -> u64 {
+    u64::try_from(value as i128 + offset).expect("mapped value out of range")
+}
+
+impl Piecewise {
+    pub fn identity() -> Piecewise {
+        Piecewise {
+            pieces: vec![Piece {
+                start: 0,
+                offset: 0,
+            }],
+        }
+    }
+
+    /// The function one stage applies: the first map covering a value moves it, anything
+    /// else stays put. Maps running past `u64::MAX` only cover what [`Map::covered`] says.
+    pub fn from_maps(maps: &[Map]) -> Piecewise {
+        // Laying the maps down last first leaves the first match on top
+        maps.iter()
+            .rev()
+            .filter_map(|map| Some((map.covered()?, map)))
+            .fold(Piecewise::identity(), |f, (covered, map)| {
+                f.overlay(covered, map.destination as i128 - map.source as i128)
+            })
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    fn piece_index(&self, value: u64) -> usize {
+        self.pieces.partition_point(|p| p.start <= value) - 1
+    }
+
+    /// Last value covered by piece `i`.
+    fn piece_end(&self, i: usize) -> u64 {
+        self.pieces
+            .get(i + 1)
+            .map_or(u64::MAX, |next| next.start - 1)
+    }
+
+    pub fn apply(&self, value: u64) -> u64 {
+        shift(value, self.pieces[self.piece_index(value)].offset)
+    }
+
+    /// Adds a piece, merging it into the one before if the offsets match.
+    fn push(pieces: &mut Vec<Piece>, piece: Piece) {
+        match pieces.last() {
+            Some(last) if last.offset == piece.offset => {}
+            _ => pieces.push(piece),
+        }
+    }
+
+    /// This function with every value in `range` moved by `offset` instead.
+    fn overlay(&self, range: RangeInclusive<u64>, offset: i128) -> Piecewise {
+        let (start, end) = range.into_inner();
+        let mut pieces = Vec::new();
+        for piece in self.pieces.iter().take_while(|p| p.start < start) {
+            Self::push(&mut pieces, *piece);
+        }
+        Self::push(&mut pieces, Piece { start, offset });
+        if end < u64::MAX {
+            let after = end + 1;
+            Self::push(
+                &mut pieces,
+                Piece {
+                    start: after,
+                    offset: self.pieces[self.piece_index(after)].offset,
+                },
+            );
+            for piece in self.pieces.iter().skip_while(|p| p.start <= after) {
+                Self::push(&mut pieces, *piece);
+            }
+        }
+        Piecewise { pieces }
+    }
+
+    /// `next` applied to the result of this function.
+    pub fn then(&self, next: &Piecewise) -> Piecewise {
+        let mut pieces = Vec::new();
+        for i in 0..self.pieces.len() {
+            let Piece { start, offset } = self.pieces[i];
+            let end = self.piece_end(i);
+            // Where this piece lands, split wherever `next` changes
+            let (image_start, image_end) = (shift(start, offset), shift(end, offset));
+            let mut j = next.piece_index(image_start);
+            loop {
+                let piece_start = next.pieces[j].start.max(image_start);
+                Self::push(
+                    &mut pieces,
+                    Piece {
+                        start: shift(piece_start, -offset),
+                        offset: offset + next.pieces[j].offset,
+                    },
+                );
+                if next.piece_end(j) >= image_end {
+                    break;
+                }
+                j += 1;
+            }
+        }
+        Piecewise { pieces }
+    }
+
+    /// Every range of inputs landing somewhere in `range`, in input order.
+    pub fn preimage(&self, range: RangeInclusive<u64>) -> Vec<RangeInclusive<u64>> {
+        let (low, high) = range.into_inner();
+        let mut ranges: Vec<RangeInclusive<u64>> = Vec::new();
+        for i in 0..self.pieces.len() {
+            let Piece { start, offset } = self.pieces[i];
+            let end = self.piece_end(i);
+            let (image_start, image_end) = (shift(start, offset), shift(end, offset));
+            let (from, to) = (image_start.max(low), image_end.min(high));
+            if from > to {
+                continue;
+            }
+
+            let (from, to) = (shift(from, -offset), shift(to, -offset));
+            match ranges.last_mut() {
+                Some(last) if *last.end() != u64::MAX && last.end() + 1 == from => {
+                    *last = *last.start()..=to
+                }
+                _ => ranges.push(from..=to),
+            }
+        }
+        ranges
+    }
+
+    /// Every input that lands on `value`. There can be more than one when a map moves values
+    /// on top of ones that stay put.
+    pub fn inverse(&self, value: u64) -> Vec<u64> {
+        self.preimage(value..=value)
+            .into_iter()
+            .flat_map(|range| range.collect::<Vec<_>>())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_maps() {
+        // The first map wins where they overlap
+        let maps = [
+            Map {
+                destination: 100,
+                source: 10,
+                range: 5,
+            },
+            Map {
+                destination: 200,
+                source: 12,
+                range: 10,
+            },
+        ];
+        let f = Piecewise::from_maps(&maps);
+        assert_eq!(
+            f.pieces(),
+            &[
+                Piece {
+                    start: 0,
+                    offset: 0
+                },
+                Piece {
+                    start: 10,
+                    offset: 90
+                },
+                Piece {
+                    start: 15,
+                    offset: 188
+                },
+                Piece {
+                    start: 22,
+                    offset: 0
+                },
+            ]
+        );
+        assert_eq!(f.apply(14), 104);
+        assert_eq!(f.apply(15), 203);
+        assert_eq!(f.apply(u64::MAX), u64::MAX);
+        // 104 is also past the maps, so it stays put
+        assert_eq!(f.inverse(104), vec![14, 104]);
+        assert_eq!(f.preimage(0..=9), vec![0..=9]);
+    }
+
+    #[test]
+    fn test_from_maps_overflow() {
+        // Sources past u64::MAX, then destinations past it
+        let maps = [
+            Map {
+                destination: 0,
+                source: u64::MAX - 1,
+                range: 5,
+            },
+            Map {
+                destination: u64::MAX - 2,
+                source: 10,
+                range: 10,
+            },
+        ];
+        let f = Piecewise::from_maps(&maps);
+        assert_eq!(f.apply(u64::MAX), 1);
+        assert_eq!(f.apply(12), u64::MAX);
+        // Would land past u64::MAX, so stays put
+        assert_eq!(f.apply(13), 13);
+        assert_eq!(maps[1].covered(), Some(10..=12));
+        for value in [9, 10, 12, 13, 19, u64::MAX - 1, u64::MAX] {
+            assert_eq!(
+                f.apply(value),
+                maps.iter()
+                    .find_map(|map| map.apply(value))
+                    .unwrap_or(value)
+            );
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::{Duration, Instant};
+
+use day_07::{parse_hands, random_hands, rank, total_winnings, Card, HandType, Ruleset};
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+// Sorts millions of generated hands the old way, comparing the hand type and then each card,
+// and by the packed keys, and checks both give the same ranking. HANDS sets how many:
+//
+//     HANDS=5000000 cargo bench --bench ranking
+fn main() {
+    let count = std::env::var("HANDS")
+        .map(|n| n.parse().expect("HANDS must be a number"))
+        .unwrap_or(3_000_000);
+    let input = random_hands(count, 2023);
+    println!("{} hands", count);
+
+    for ruleset in [Ruleset::standard(), Ruleset::jokers()] {
+        println!("{}", ruleset.name);
+        let (mut hands, parse_time) = time(|| parse_hands(&input, &ruleset));
+        println!("{:>24}: {:?}", "parse", parse_time);
+
+        let mut old: Vec<(HandType, Vec<Card>, u32)> = hands
+            .iter()
+            .map(|hand| (hand.hand_type, hand.cards.clone(), hand.bid))
+            .collect();
+        let ((), old_time) = time(|| old.sort_unstable());
+        println!("{:>24}: {:?}", "type then cards", old_time);
+        let ((), key_time) = time(|| rank(&mut hands));
+        println!("{:>24}: {:?}", "packed keys", key_time);
+
+        assert!(hands
+            .iter()
+            .zip(&old)
+            .all(|(hand, (hand_type, cards, bid))| {
+                hand.hand_type == *hand_type && hand.cards == *cards && hand.bid == *bid
+            }));
+        let winnings = total_winnings(&hands).unwrap();
+        println!("{:>24}: identical, winnings {}", "ranking", winnings);
+        println!(
+            "{:>24}: {:.1}x",
+            "speedup",
+            old_time.as_secs_f64() / key_time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use day_07::ranking::explain;
+use day_07::{Hand, Ruleset};
+
+// Usage: explain <hand A> <hand B> [standard|jokers|ruleset file]
+// Says which hand ranks higher and what decides it.
+fn main() {
+    let usage = "Usage: explain <hand A> <hand B> [standard|jokers|ruleset file]";
+    let mut args = std::env::args().skip(1);
+    let (a, b) = match (args.next(), args.next()) {
+        (Some(a), Some(b)) => (a, b),
+        _ => {
+            eprintln!("{}", usage);
+            std::process::exit(1);
+        }
+    };
+    let ruleset =
+        Ruleset::from_arg(&args.next().unwrap_or("standard".to_string())).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+
+    let hand = |faces: &str| {
+        let cards = ruleset.parse_cards(faces).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        if cards.len() != ruleset.hand_size() {
+            eprintln!(
+                "{} has {} cards, a hand has {}",
+                faces,
+                cards.len(),
+                ruleset.hand_size()
+            );
+            std::process::exit(1);
+        }
+        Hand::new(cards, 0, &ruleset)
+    };
+    let (a, b) = (hand(&a), hand(&b));
+
+    println!("{}", explain(&a, &b, &ruleset));
+}
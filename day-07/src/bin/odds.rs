@@ -0,0 +1,50 @@
+use day_07::odds::odds;
+use day_07::Ruleset;
+
+// Usage: odds <known cards> [standard|jokers|ruleset file]
+// Prints the chance of ending up with each hand type when the rest of the hand is drawn.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let known = args
+        .next()
+        .expect("Usage: odds <known cards> [standard|jokers|ruleset file]");
+    let ruleset =
+        Ruleset::from_arg(&args.next().unwrap_or("standard".to_string())).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+
+    let known = ruleset.parse_cards(&known).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let odds = odds(&known, &ruleset).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+
+    println!(
+        "{} known, {} drawn, {} equally likely draws",
+        known.len(),
+        ruleset.hand_size() - known.len(),
+        odds.total
+    );
+    let hand_types = ruleset.hand_types();
+    let width = hand_types
+        .iter()
+        .map(|hand_type| hand_type.to_string().len())
+        .max()
+        .unwrap();
+    println!(
+        "{:<width$} {:>10} {:>12}",
+        "hand type", "draws", "probability"
+    );
+    for hand_type in hand_types {
+        println!(
+            "{:<width$} {:>10} {:>12.6}",
+            hand_type.to_string(),
+            odds.counts.get(&hand_type).copied().unwrap_or(0),
+            odds.probability(hand_type)
+        );
+    }
+}
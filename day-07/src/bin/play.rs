@@ -0,0 +1,24 @@
+use day_07::{winnings, Ruleset};
+
+// Usage: play [standard|jokers|ruleset file] [hands file]
+// Ruleset files are described on `Ruleset::from_config`, with examples in rulesets/.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let ruleset =
+        Ruleset::from_arg(&args.next().unwrap_or("standard".to_string())).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    let input = match args.next() {
+        Some(path) => std::fs::read_to_string(path).expect("Couldn't read hands"),
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    match winnings(&input, &ruleset) {
+        Ok(winnings) => println!("{}: {}", ruleset.name, winnings),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
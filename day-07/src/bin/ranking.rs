@@ -0,0 +1,26 @@
+use day_07::parse_hands;
+use day_07::ranking::{ranking, to_table};
+use day_07::Ruleset;
+
+// Usage: ranking [standard|jokers|ruleset file] [hands file]
+// Prints every hand from weakest to strongest with what it scored.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let ruleset =
+        Ruleset::from_arg(&args.next().unwrap_or("standard".to_string())).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    let input = match args.next() {
+        Some(path) => std::fs::read_to_string(path).expect("Couldn't read hands"),
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    match ranking(parse_hands(&input, &ruleset), &ruleset) {
+        Ok(rows) => print!("{}", to_table(&rows)),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
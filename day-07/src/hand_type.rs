@@ -0,0 +1,210 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+pub const MIN_HAND_SIZE: usize = 3;
+pub const MAX_HAND_SIZE: usize = 7;
+
+/// Bits each group size takes up in [`HandType::packed`]
+const COUNT_BITS: u32 = 3;
+/// How many bits [`HandType::packed`] uses
+pub const PACKED_BITS: u32 = COUNT_BITS * MAX_HAND_SIZE as u32 + 1;
+
+/// What a hand is made of: the sizes of its groups of matching cards, or a straight.
+///
+/// Hands with a bigger largest group are stronger, then those with a bigger second largest
+/// group and so on, which for five cards gives the usual order from high card up to five of a
+/// kind. A straight ranks just above three of a kind with no other group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandType {
+    // Largest first, padded with zeroes
+    counts: [u8; MAX_HAND_SIZE],
+    straight: bool,
+}
+
+impl HandType {
+    /// A hand type from the sizes of its groups, in any order.
+    pub fn new(counts: &[u8]) -> HandType {
+        assert!(
+            counts.len() <= MAX_HAND_SIZE,
+            "too many groups: {:?}",
+            counts
+        );
+        let mut sorted = [0; MAX_HAND_SIZE];
+        sorted[..counts.len()].copy_from_slice(counts);
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        HandType {
+            counts: sorted,
+            straight: false,
+        }
+    }
+
+    /// `size` cards in a row.
+    pub fn straight(size: usize) -> HandType {
+        HandType {
+            straight: true,
+            ..HandType::new(&vec![1; size])
+        }
+    }
+
+    /// Every hand type for hands of `size` cards, weakest first.
+    pub fn all(size: usize, straights: bool) -> Vec<HandType> {
+        // Partitions of `left` into parts no bigger than `largest`
+        fn partitions(left: u8, largest: u8, parts: &mut Vec<u8>, out: &mut Vec<HandType>) {
+            if left == 0 {
+                out.push(HandType::new(parts));
+                return;
+            }
+            for part in (1..=largest.min(left)).rev() {
+                parts.push(part);
+                partitions(left - part, part, parts, out);
+                parts.pop();
+            }
+        }
+
+        let mut all = Vec::new();
+        partitions(size as u8, size as u8, &mut Vec::new(), &mut all);
+        if straights {
+            all.push(HandType::straight(size));
+        }
+        all.sort();
+        all
+    }
+
+    /// Sizes of the groups of matching cards, largest first. A straight is all ones.
+    pub fn counts(&self) -> &[u8] {
+        let groups = self.counts.iter().take_while(|&&c| c > 0).count();
+        &self.counts[..groups]
+    }
+
+    pub fn is_straight(&self) -> bool {
+        self.straight
+    }
+
+    /// The groups this hand type ranks as, so a straight counts as three of a kind.
+    fn rank(&self) -> ([u8; MAX_HAND_SIZE], bool) {
+        if !self.straight {
+            return (self.counts, false);
+        }
+        let size: u8 = self.counts.iter().sum();
+        let mut counts = [0; MAX_HAND_SIZE];
+        counts[0] = 3;
+        counts[1..size as usize - 2].fill(1);
+        (counts, true)
+    }
+
+    /// The rank packed into the low [`PACKED_BITS`] bits, so stronger hand types pack to
+    /// bigger numbers.
+    pub fn packed(&self) -> u64 {
+        let (counts, straight) = self.rank();
+        counts
+            .iter()
+            .fold(0, |packed, &count| packed << COUNT_BITS | count as u64)
+            << 1
+            | straight as u64
+    }
+}
+
+impl Ord for HandType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for HandType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+const NUMBERS: [&str; 8] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+];
+const PLURALS: [&str; 8] = [
+    "zeroes", "ones", "twos", "threes", "fours", "fives", "sixes", "sevens",
+];
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = self.counts();
+        if self.straight {
+            return write!(f, "Straight");
+        }
+        if counts == [3, 2] {
+            return write!(f, "Full house");
+        }
+        if counts[0] == 1 {
+            return write!(f, "High card");
+        }
+
+        let mut parts = Vec::new();
+        for size in (2..=counts[0]).rev() {
+            let groups = counts.iter().filter(|&&c| c == size).count();
+            parts.push(match (size, groups) {
+                (_, 0) => continue,
+                (2, 1) => "one pair".to_string(),
+                (2, _) => format!("{} pairs", NUMBERS[groups]),
+                (_, 1) => format!("{} of a kind", NUMBERS[size as usize]),
+                (_, _) => format!("{} {} of a kind", NUMBERS[groups], PLURALS[size as usize]),
+            });
+        }
+        let description = parts.join(" and ");
+        let mut chars = description.chars();
+        let first = chars.next().unwrap().to_ascii_uppercase();
+        write!(f, "{}{}", first, chars.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_five_card_order() {
+        let names: Vec<String> = HandType::all(5, true)
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "High card",
+                "One pair",
+                "Two pairs",
+                "Three of a kind",
+                "Straight",
+                "Full house",
+                "Four of a kind",
+                "Five of a kind",
+            ]
+        );
+        assert!(HandType::all(5, true)
+            .windows(2)
+            .all(|pair| pair[0].packed() < pair[1].packed()));
+    }
+
+    #[test]
+    fn test_other_sizes() {
+        // With three cards nothing ranks above three of a kind, so a straight is the best
+        let three = HandType::all(3, true);
+        assert_eq!(three.last(), Some(&HandType::straight(3)));
+        assert_eq!(three.len(), 4);
+
+        let seven = HandType::all(7, true);
+        assert_eq!(seven.len(), 15 + 1);
+        assert!(seven
+            .windows(2)
+            .all(|pair| pair[0].packed() < pair[1].packed()));
+        assert!(HandType::straight(7) < HandType::new(&[3, 2, 1, 1]));
+        assert!(HandType::new(&[3, 1, 1, 1, 1]) < HandType::straight(7));
+        assert_eq!(
+            HandType::new(&[2, 3, 2]).to_string(),
+            "Three of a kind and two pairs"
+        );
+        assert_eq!(HandType::new(&[2, 3, 2]).counts(), &[3, 2, 2]);
+        assert_eq!(
+            HandType::new(&[3, 3, 1]).to_string(),
+            "Two threes of a kind"
+        );
+        assert_eq!(HandType::new(&[2, 2, 2, 1]).to_string(), "Three pairs");
+    }
+}
@@ -0,0 +1,555 @@
+pub mod hand_type;
+pub mod odds;
+pub mod ranking;
+
+use nom::{
+    bytes::complete::tag, character::complete::anychar, combinator::map_opt, multi::count,
+    sequence::separated_pair, IResult,
+};
+
+pub use hand_type::HandType;
+use hand_type::{MAX_HAND_SIZE, MIN_HAND_SIZE, PACKED_BITS};
+
+/// A card face along with how strong it is under the ruleset it was read with. Cards only
+/// compare sensibly with cards from the same ruleset.
+#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Clone, Copy, Hash)]
+pub struct Card {
+    pub strength: u8,
+    pub face: char,
+}
+
+/// Bits each card takes up in a [`Hand::key`], so a ruleset can't have more faces than fit.
+const CARD_BITS: u32 = 6;
+pub const MAX_FACES: usize = 1 << CARD_BITS;
+
+// A key holds the hand type and then every card
+const _: () = assert!(PACKED_BITS + CARD_BITS * MAX_HAND_SIZE as u32 <= u64::BITS);
+
+/// How cards are ordered, which of them are wild, how many make a hand and whether straights
+/// count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruleset {
+    pub name: String,
+    // Faces from weakest to strongest
+    order: Vec<char>,
+    wild: Vec<char>,
+    hand_size: usize,
+    straights: bool,
+}
+
+impl Ruleset {
+    /// Part 1: no wild cards, aces high.
+    pub fn standard() -> Ruleset {
+        Ruleset {
+            name: "standard".to_string(),
+            order: "23456789TJQKA".chars().collect(),
+            wild: Vec::new(),
+            hand_size: 5,
+            straights: false,
+        }
+    }
+
+    /// Part 2: `J` is a joker, wild but the weakest card on its own.
+    pub fn jokers() -> Ruleset {
+        Ruleset {
+            name: "jokers".to_string(),
+            order: "J23456789TQKA".chars().collect(),
+            wild: vec!['J'],
+            hand_size: 5,
+            straights: false,
+        }
+    }
+
+    /// Reads a ruleset from `key = value` lines. `order` lists every face from weakest to
+    /// strongest, `wild` the faces that are wild (none if left out), `size` the cards in a
+    /// hand (3 to 7, 5 if left out) and `straights` is `yes` or `no` (the default). `name` is
+    /// optional. Blank lines and lines starting with `#` are skipped.
+    ///
+    /// ```text
+    /// # Twos wild
+    /// name = twos
+    /// order = 23456789TJQKA
+    /// wild = 2
+    /// size = 7
+    /// straights = yes
+    /// ```
+    pub fn from_config(config: &str) -> Result<Ruleset, String> {
+        let mut name = None;
+        let mut order = None;
+        let mut wild = None;
+        let mut size = None;
+        let mut straights = None;
+        for line in config.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Expected key = value: {}", line))?;
+            let value = value.trim();
+            let slot = match key.trim() {
+                "name" => &mut name,
+                "order" => &mut order,
+                "wild" => &mut wild,
+                "size" => &mut size,
+                "straights" => &mut straights,
+                other => return Err(format!("Unknown setting: {}", other)),
+            };
+            if slot.replace(value).is_some() {
+                return Err(format!("{} is set more than once", key.trim()));
+            }
+        }
+
+        let order: Vec<char> = order
+            .ok_or("Missing order")?
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        if order.is_empty() {
+            return Err("order can't be empty".to_string());
+        }
+        if order.len() > MAX_FACES {
+            return Err(format!("order can't have more than {} faces", MAX_FACES));
+        }
+        for (i, face) in order.iter().enumerate() {
+            if order[..i].contains(face) {
+                return Err(format!("{} appears more than once in order", face));
+            }
+        }
+
+        let wild: Vec<char> = wild
+            .unwrap_or("")
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        if let Some(face) = wild.iter().find(|face| !order.contains(face)) {
+            return Err(format!("Wild card {} isn't in order", face));
+        }
+
+        let hand_size = match size {
+            None => 5,
+            Some(size) => size
+                .parse()
+                .ok()
+                .filter(|size| (MIN_HAND_SIZE..=MAX_HAND_SIZE).contains(size))
+                .ok_or_else(|| {
+                    format!(
+                        "size must be from {} to {}, not {}",
+                        MIN_HAND_SIZE, MAX_HAND_SIZE, size
+                    )
+                })?,
+        };
+        let straights = match straights {
+            None | Some("no") => false,
+            Some("yes") => true,
+            Some(other) => return Err(format!("straights must be yes or no, not {}", other)),
+        };
+
+        Ok(Ruleset {
+            name: name.unwrap_or("custom").to_string(),
+            order,
+            wild,
+            hand_size,
+            straights,
+        })
+    }
+
+    /// `standard`, `jokers`, or else the path of a config file to read with
+    /// [`Ruleset::from_config`].
+    pub fn from_arg(arg: &str) -> Result<Ruleset, String> {
+        match arg {
+            "standard" => Ok(Ruleset::standard()),
+            "jokers" => Ok(Ruleset::jokers()),
+            path => {
+                let config = std::fs::read_to_string(path)
+                    .map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+                Ruleset::from_config(&config).map_err(|e| format!("{}: {}", path, e))
+            }
+        }
+    }
+
+    /// Every card there is, weakest first.
+    pub fn cards(&self) -> impl Iterator<Item = Card> + '_ {
+        self.order.iter().enumerate().map(|(i, &face)| Card {
+            strength: i as u8,
+            face,
+        })
+    }
+
+    pub fn card(&self, face: char) -> Option<Card> {
+        self.order.iter().position(|&f| f == face).map(|i| Card {
+            strength: i as u8,
+            face,
+        })
+    }
+
+    /// Reads a hand's worth of faces, like `KTJJT`.
+    pub fn parse_cards(&self, faces: &str) -> Result<Vec<Card>, String> {
+        faces
+            .chars()
+            .map(|c| {
+                self.card(c)
+                    .ok_or_else(|| format!("{} isn't a card in the {} ruleset", c, self.name))
+            })
+            .collect()
+    }
+
+    pub fn is_wild(&self, card: &Card) -> bool {
+        self.wild.contains(&card.face)
+    }
+
+    pub fn hand_size(&self) -> usize {
+        self.hand_size
+    }
+
+    pub fn straights(&self) -> bool {
+        self.straights
+    }
+
+    /// Every hand type a hand can have under these rules, weakest first.
+    pub fn hand_types(&self) -> Vec<HandType> {
+        HandType::all(self.hand_size, self.straights)
+    }
+
+    /// Where `strength` comes in the order once wild faces are left out, which is what
+    /// straights run along.
+    fn rung(&self, strength: u8) -> usize {
+        self.order[..strength as usize]
+            .iter()
+            .filter(|face| !self.wild.contains(face))
+            .count()
+    }
+
+    /// The hand with each wild card replaced by the card it stands in for: the strongest of
+    /// the largest group, or the missing cards of a straight. Which one it picks doesn't
+    /// change the hand type.
+    pub fn effective_cards(&self, cards: &[Card]) -> Vec<Card> {
+        let natural: Vec<Card> = self.cards().filter(|card| !self.is_wild(card)).collect();
+        if natural.is_empty() || !cards.iter().any(|card| self.is_wild(card)) {
+            return cards.to_vec();
+        }
+        let kept: Vec<&Card> = cards.iter().filter(|card| !self.is_wild(card)).collect();
+
+        let mut stand_ins: Vec<Card> = if self.determine_hand_type(cards).is_straight() {
+            // The highest run that still takes in every card kept
+            let rungs: Vec<usize> = kept.iter().map(|card| self.rung(card.strength)).collect();
+            let low = rungs.iter().copied().min().unwrap_or(natural.len());
+            let start = low.min(natural.len() - cards.len());
+            (start..start + cards.len())
+                .filter(|rung| !rungs.contains(rung))
+                .map(|rung| natural[rung])
+                .collect()
+        } else {
+            let best = kept
+                .iter()
+                .max_by_key(|card| (kept.iter().filter(|other| other == card).count(), **card))
+                .map_or(natural[natural.len() - 1], |card| **card);
+            vec![best; cards.len() - kept.len()]
+        };
+
+        stand_ins.reverse();
+        cards
+            .iter()
+            .map(|card| match self.is_wild(card) {
+                true => stand_ins.pop().unwrap(),
+                false => *card,
+            })
+            .collect()
+    }
+
+    /// Wild cards join whichever group of cards is already largest, which always makes the
+    /// strongest group. With straights they can also fill the gaps in a run instead, if that
+    /// ranks higher.
+    pub fn determine_hand_type(&self, cards: &[Card]) -> HandType {
+        let mut strengths: Vec<u8> = cards
+            .iter()
+            .filter(|card| !self.is_wild(card))
+            .map(|card| card.strength)
+            .collect();
+        let wild_count = (cards.len() - strengths.len()) as u8;
+        strengths.sort_unstable();
+
+        let mut card_counts: Vec<u8> = strengths
+            .chunk_by(|a, b| a == b)
+            .map(|group| group.len() as u8)
+            .collect();
+        let distinct = card_counts.len() == strengths.len();
+        card_counts.sort_unstable_by(|a, b| b.cmp(a));
+        match card_counts.first_mut() {
+            Some(largest) => *largest += wild_count,
+            None => card_counts.push(wild_count),
+        }
+        let grouped = HandType::new(&card_counts);
+
+        let rungs = self.order.len() - self.wild.len();
+        let straight = self.straights
+            && distinct
+            && cards.len() <= rungs
+            && match (strengths.first(), strengths.last()) {
+                (Some(&low), Some(&high)) => self.rung(high) - self.rung(low) < cards.len(),
+                _ => true,
+            };
+        if straight {
+            grouped.max(HandType::straight(cards.len()))
+        } else {
+            grouped
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+    pub hand_type: HandType,
+    pub bid: u32,
+    /// The hand type followed by each card's strength, packed so that stronger hands have
+    /// bigger keys. Sorting on this avoids comparing the cards one by one.
+    pub key: u64,
+}
+
+impl Hand {
+    pub fn new(cards: Vec<Card>, bid: u32, ruleset: &Ruleset) -> Hand {
+        let hand_type = ruleset.determine_hand_type(&cards);
+        let key = cards.iter().fold(hand_type.packed(), |key, card| {
+            key << CARD_BITS | card.strength as u64
+        });
+        Hand {
+            cards,
+            hand_type,
+            bid,
+            key,
+        }
+    }
+}
+
+pub fn parse_hand<'a>(input: &'a str, ruleset: &Ruleset) -> IResult<&'a str, Hand> {
+    let card = map_opt(anychar, |c| ruleset.card(c));
+    let (input, (cards, bid)) = separated_pair(
+        count(card, ruleset.hand_size),
+        tag(" "),
+        nom::character::complete::u32,
+    )(input)?;
+    Ok((input, Hand::new(cards, bid, ruleset)))
+}
+
+pub fn parse_hands(input: &str, ruleset: &Ruleset) -> Vec<Hand> {
+    input
+        .lines()
+        .map(|line| parse_hand(line, ruleset).unwrap().1)
+        .collect()
+}
+
+/// Sorts hands weakest first. Equal hands go by bid, smallest first.
+pub fn rank(hands: &mut [Hand]) {
+    hands.sort_unstable_by_key(|hand| (hand.key, hand.bid));
+}
+
+/// What a hand wins at `rank`, the weakest hand ranking 1, or `None` if it doesn't fit in a
+/// `u64`.
+pub fn hand_winnings(bid: u32, rank: usize) -> Option<u64> {
+    (bid as u64).checked_mul(u64::try_from(rank).ok()?)
+}
+
+/// Total winnings of hands already put in order by [`rank`].
+pub fn total_winnings(ranked: &[Hand]) -> Result<u64, String> {
+    ranked
+        .iter()
+        .enumerate()
+        .try_fold(0u64, |total, (i, hand)| {
+            hand_winnings(hand.bid, i + 1)
+                .and_then(|winnings| total.checked_add(winnings))
+                .ok_or_else(|| format!("winnings overflow a u64 at rank {}", i + 1))
+        })
+}
+
+/// Total winnings: each hand's bid times its rank, the weakest hand ranking 1.
+pub fn winnings(input: &str, ruleset: &Ruleset) -> Result<u64, String> {
+    let mut hands = parse_hands(input, ruleset);
+    rank(&mut hands);
+    total_winnings(&hands)
+}
+
+/// `count` random hands of standard cards, one `CARDS bid` per line with bids from 1 to
+/// 1000. The same `seed` always gives the same hands.
+pub fn random_hands(count: usize, seed: u64) -> String {
+    let faces: Vec<char> = "23456789TJQKA".chars().collect();
+    let mut state = seed;
+    let mut next = |below: u64| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) % below
+    };
+    let mut input = String::with_capacity(count * 10);
+    for _ in 0..count {
+        for _ in 0..5 {
+            input.push(faces[next(13) as usize]);
+        }
+        input.push(' ');
+        input.push_str(&(1 + next(1000)).to_string());
+        input.push('\n');
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand_type(cards: &str, ruleset: &Ruleset) -> HandType {
+        let cards: Vec<Card> = cards.chars().map(|c| ruleset.card(c).unwrap()).collect();
+        ruleset.determine_hand_type(&cards)
+    }
+
+    #[test]
+    fn test_presets() {
+        let test = include_str!("../test.txt");
+        assert_eq!(winnings(test, &Ruleset::standard()), Ok(6440));
+        assert_eq!(winnings(test, &Ruleset::jokers()), Ok(5905));
+
+        // The largest bids add up past a u64 after 92682 hands
+        let input = "AAAAA 4294967295\n".repeat(100_000);
+        assert_eq!(
+            winnings(&input, &Ruleset::standard()),
+            Err("winnings overflow a u64 at rank 92682".to_string())
+        );
+        assert!(winnings(&input[..17 * 92681], &Ruleset::standard()).is_ok());
+
+        let jokers = Ruleset::jokers();
+        assert_eq!(hand_type("JJJJJ", &jokers), HandType::new(&[5]));
+        assert_eq!(hand_type("KTJJT", &jokers), HandType::new(&[4, 1]));
+        assert!(jokers.card('J') < jokers.card('2'));
+    }
+
+    #[test]
+    fn test_key_ranking() {
+        // Sorting by key has to agree with comparing the type, then the cards in order,
+        // then the bid, which is how hands used to be sorted
+        let input = random_hands(2000, 7);
+        for ruleset in [Ruleset::standard(), Ruleset::jokers()] {
+            let mut hands = parse_hands(&input, &ruleset);
+            let mut expected: Vec<(HandType, Vec<Card>, u32)> = hands
+                .iter()
+                .map(|hand| (hand.hand_type, hand.cards.clone(), hand.bid))
+                .collect();
+            expected.sort();
+            rank(&mut hands);
+            let ranked: Vec<(HandType, Vec<Card>, u32)> = hands
+                .into_iter()
+                .map(|hand| (hand.hand_type, hand.cards, hand.bid))
+                .collect();
+            assert_eq!(ranked, expected);
+        }
+    }
+
+    #[test]
+    fn test_hand_sizes() {
+        let seven = Ruleset::from_config("order = 23456789TJQKA\nwild = J\nsize = 7").unwrap();
+        assert_eq!(hand_type("22233JJ", &seven), HandType::new(&[5, 2]));
+        assert_eq!(hand_type("JJJJJJJ", &seven), HandType::new(&[7]));
+        assert_eq!(hand_type("2345678", &seven), HandType::new(&[1; 7]));
+        let hands = parse_hands("2223334 5\nAKQT987 6", &seven);
+        assert_eq!(hands[0].hand_type, HandType::new(&[3, 3, 1]));
+        assert!(parse_hand("22233 5", &seven).is_err());
+
+        let three = Ruleset::from_config("order = 23456789TJQKA\nsize = 3").unwrap();
+        assert_eq!(
+            winnings("AA2 10\nKKK 1\n345 100", &three),
+            Ok(100 + 2 * 10 + 3)
+        );
+    }
+
+    #[test]
+    fn test_straights() {
+        let straights = Ruleset::from_config(
+            "order = J23456789TQKA\n\
+             wild = J\n\
+             straights = yes",
+        )
+        .unwrap();
+        assert_eq!(hand_type("23456", &straights), HandType::straight(5));
+        assert_eq!(hand_type("TQKA9", &straights), HandType::straight(5));
+        assert_eq!(hand_type("2345J", &straights), HandType::straight(5));
+        // Jokers fill the gaps, unless they make something better by joining a group
+        assert_eq!(hand_type("2J4J6", &straights), HandType::straight(5));
+        assert_eq!(hand_type("2J3J3", &straights), HandType::new(&[4, 1]));
+        assert_eq!(hand_type("2345K", &straights), HandType::new(&[1; 5]));
+        assert_eq!(hand_type("2234J", &straights), HandType::new(&[3, 1, 1]));
+        assert!(HandType::new(&[3, 1, 1]) < hand_type("2345J", &straights));
+        assert!(hand_type("2345J", &straights) < HandType::new(&[3, 2]));
+
+        // Three jokers and two close cards: four of a kind beats the straight
+        assert_eq!(hand_type("JJJ23", &straights), HandType::new(&[4, 1]));
+        assert!(!Ruleset::standard().straights());
+        assert_eq!(
+            hand_type("23456", &Ruleset::standard()),
+            HandType::new(&[1; 5])
+        );
+    }
+
+    #[test]
+    fn test_effective_cards() {
+        // The effective hand, read without any wild cards, has the same hand type
+        fn effective(faces: &str, ruleset: &Ruleset, natural: &Ruleset) -> String {
+            let cards = ruleset.parse_cards(faces).unwrap();
+            let effective: String = ruleset
+                .effective_cards(&cards)
+                .iter()
+                .map(|card| card.face)
+                .collect();
+            assert_eq!(
+                natural.determine_hand_type(&natural.parse_cards(&effective).unwrap()),
+                ruleset.determine_hand_type(&cards),
+                "{}",
+                faces
+            );
+            effective
+        }
+
+        let straights = Ruleset::from_config(
+            "order = J23456789TQKA\n\
+             wild = J\n\
+             straights = yes",
+        )
+        .unwrap();
+        let natural = Ruleset::from_config("order = 23456789TQKA\nstraights = yes").unwrap();
+        assert_eq!(effective("2J4J6", &straights, &natural), "23456");
+        assert_eq!(effective("JQKAJ", &straights, &natural), "9QKAT");
+        assert_eq!(effective("2J3J3", &straights, &natural), "23333");
+
+        let jokers = Ruleset::jokers();
+        let natural = Ruleset::from_config("order = 23456789TQKA").unwrap();
+        assert_eq!(effective("KTJJT", &jokers, &natural), "KTTTT");
+        assert_eq!(effective("JJJJJ", &jokers, &natural), "AAAAA");
+        assert_eq!(effective("32T3K", &jokers, &natural), "32T3K");
+    }
+
+    #[test]
+    fn test_from_config() {
+        let twos = Ruleset::from_config(
+            "# Twos wild\n\
+             name = twos\n\
+             order = 23456789TJQKA\n\
+             wild = 2\n",
+        )
+        .unwrap();
+        assert_eq!(twos.name, "twos");
+        assert_eq!(hand_type("2KK2A", &twos), HandType::new(&[4, 1]));
+        assert_eq!(hand_type("JKK2A", &twos), HandType::new(&[3, 1, 1]));
+
+        let ace_low = Ruleset::from_config("order = A23456789TJQK").unwrap();
+        assert_eq!(ace_low.name, "custom");
+        assert!(ace_low.card('A') < ace_low.card('2'));
+        assert_eq!(ace_low.card('1'), None);
+        assert!(parse_hand("1KKKK 10", &ace_low).is_err());
+
+        assert!(Ruleset::from_config("wild = 2").is_err());
+        assert!(Ruleset::from_config("order = 23456789TJQKA\nwild = 1").is_err());
+        assert!(Ruleset::from_config("order = 23456789TJQKAA").is_err());
+        assert!(Ruleset::from_config("order = 23456789TJQKA\nlow = A").is_err());
+        assert!(Ruleset::from_config("order = 23456789TJQKA\nsize = 8").is_err());
+        assert!(Ruleset::from_config("order = 23456789TJQKA\nstraights = maybe").is_err());
+        let too_many: String = ('!'..='~').collect();
+        assert!(Ruleset::from_config(&format!("order = {}", too_many)).is_err());
+    }
+}
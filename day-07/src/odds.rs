@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use crate::{Card, HandType, Ruleset};
+
+/// How many of the equally likely ways of finishing a hand end up as each hand type.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Odds {
+    pub counts: BTreeMap<HandType, u64>,
+    pub total: u64,
+}
+
+impl Odds {
+    pub fn probability(&self, hand_type: HandType) -> f64 {
+        self.counts.get(&hand_type).copied().unwrap_or(0) as f64 / self.total as f64
+    }
+}
+
+/// Finishes the hand every possible way, each missing card being any face of `ruleset` with
+/// equal chance. There's no deck to run out of, so faces can repeat however often.
+pub fn odds(known: &[Card], ruleset: &Ruleset) -> Result<Odds, String> {
+    let size = ruleset.hand_size();
+    if known.len() > size {
+        return Err(format!("A hand has {} cards, not {}", size, known.len()));
+    }
+    let faces: Vec<Card> = ruleset.cards().collect();
+    let missing = size - known.len();
+
+    let mut counts = BTreeMap::new();
+    let mut total = 0;
+    let mut hand = known.to_vec();
+    // Counts through every draw in base `faces.len()`, one digit per missing card
+    let mut digits = vec![0; missing];
+    loop {
+        hand.truncate(known.len());
+        hand.extend(digits.iter().map(|&d| faces[d]));
+        *counts
+            .entry(ruleset.determine_hand_type(&hand))
+            .or_insert(0) += 1;
+        total += 1;
+
+        match digits.iter().rposition(|&d| d + 1 < faces.len()) {
+            Some(i) => {
+                digits[i] += 1;
+                digits[i + 1..].fill(0);
+            }
+            None => break,
+        }
+    }
+
+    Ok(Odds { counts, total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(faces: &str, ruleset: &Ruleset) -> Vec<Card> {
+        faces.chars().map(|c| ruleset.card(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_odds() {
+        let standard = Ruleset::standard();
+        let odds_aaa = odds(&cards("AAA", &standard), &standard).unwrap();
+        assert_eq!(odds_aaa.total, 13 * 13);
+        assert_eq!(
+            odds_aaa.counts,
+            BTreeMap::from([
+                (HandType::new(&[3, 1, 1]), 12 * 11),
+                (HandType::new(&[3, 2]), 12),
+                (HandType::new(&[4, 1]), 2 * 12),
+                (HandType::new(&[5]), 1),
+            ])
+        );
+        assert_eq!(odds_aaa.probability(HandType::new(&[5])), 1.0 / 169.0);
+        assert_eq!(odds_aaa.probability(HandType::new(&[1; 5])), 0.0);
+
+        // Three jokers make at least four of a kind whatever comes next
+        let jokers = Ruleset::jokers();
+        let odds_jjj = odds(&cards("JJJ", &jokers), &jokers).unwrap();
+        assert_eq!(
+            odds_jjj.counts,
+            BTreeMap::from([
+                (HandType::new(&[4, 1]), 12 * 11),
+                (HandType::new(&[5]), 12 + 1 + 2 * 12),
+            ])
+        );
+
+        let full = odds(&cards("KTJJT", &jokers), &jokers).unwrap();
+        assert_eq!(full.total, 1);
+        assert_eq!(full.counts, BTreeMap::from([(HandType::new(&[4, 1]), 1)]));
+        assert!(odds(&cards("AAAAAA", &standard), &standard).is_err());
+    }
+}
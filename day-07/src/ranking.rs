@@ -0,0 +1,257 @@
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use crate::{hand_winnings, rank, total_winnings, Card, Hand, HandType, Ruleset};
+
+fn faces(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.face).collect()
+}
+
+/// One line of the final ranking.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Row {
+    /// 1 for the weakest hand
+    pub rank: usize,
+    pub cards: String,
+    pub hand_type: HandType,
+    /// The hand with wild cards replaced by what they stand in for
+    pub effective: String,
+    pub bid: u32,
+    pub winnings: u64,
+}
+
+/// The hands in rank order, or an error if their winnings don't fit in a `u64`.
+pub fn ranking(mut hands: Vec<Hand>, ruleset: &Ruleset) -> Result<Vec<Row>, String> {
+    rank(&mut hands);
+    // Checked up front, so every row and the total in `to_table` fit
+    total_winnings(&hands)?;
+    let rows = hands
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| Row {
+            rank: i + 1,
+            cards: faces(&hand.cards),
+            hand_type: hand.hand_type,
+            effective: faces(&ruleset.effective_cards(&hand.cards)),
+            bid: hand.bid,
+            winnings: hand_winnings(hand.bid, i + 1).unwrap(),
+        })
+        .collect();
+    Ok(rows)
+}
+
+/// The rows from [`ranking`] as a text table, with the total winnings underneath.
+pub fn to_table(rows: &[Row]) -> String {
+    let headings = ["rank", "hand", "type", "effective", "bid", "winnings"];
+    let cells: Vec<[String; 6]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.rank.to_string(),
+                row.cards.clone(),
+                row.hand_type.to_string(),
+                row.effective.clone(),
+                row.bid.to_string(),
+                row.winnings.to_string(),
+            ]
+        })
+        .collect();
+    let widths: Vec<usize> = (0..headings.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|row| row[i].len())
+                .chain([headings[i].len()])
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    let mut table = String::new();
+    let mut line = |cells: [&str; 6]| {
+        // Text columns on the left, numbers on the right
+        let line = format!(
+            "{:>w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {:>w4$}  {:>w5$}",
+            cells[0],
+            cells[1],
+            cells[2],
+            cells[3],
+            cells[4],
+            cells[5],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w4 = widths[4],
+            w5 = widths[5],
+        );
+        writeln!(table, "{}", line.trim_end()).unwrap();
+    };
+    line(headings);
+    for row in &cells {
+        line(row.each_ref().map(String::as_str));
+    }
+    let total: u64 = rows.iter().map(|row| row.winnings).sum();
+    writeln!(table, "total winnings: {}", total).unwrap();
+    table
+}
+
+/// What decides between two hands.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reason {
+    HandType,
+    /// The types match, so it's the first card (counting from 0) that differs
+    Card(usize),
+    /// Same cards, so they're tied
+    Identical,
+}
+
+/// How `a` compares to `b`, ignoring bids, and why.
+pub fn verdict(a: &Hand, b: &Hand) -> (Ordering, Reason) {
+    if a.hand_type != b.hand_type {
+        return (a.hand_type.cmp(&b.hand_type), Reason::HandType);
+    }
+    match a.cards.iter().zip(&b.cards).position(|(x, y)| x != y) {
+        Some(i) => (a.cards[i].cmp(&b.cards[i]), Reason::Card(i)),
+        None => (Ordering::Equal, Reason::Identical),
+    }
+}
+
+/// Says which of `a` and `b` ranks higher and why, in a sentence.
+pub fn explain(a: &Hand, b: &Hand, ruleset: &Ruleset) -> String {
+    let describe = |hand: &Hand| {
+        let effective = ruleset.effective_cards(&hand.cards);
+        if effective == hand.cards {
+            format!("{} ({})", faces(&hand.cards), hand.hand_type)
+        } else {
+            format!(
+                "{} ({}, played as {})",
+                faces(&hand.cards),
+                hand.hand_type,
+                faces(&effective)
+            )
+        }
+    };
+
+    let (ordering, reason) = verdict(a, b);
+    let (winner, loser) = match ordering {
+        Ordering::Less => (b, a),
+        _ => (a, b),
+    };
+    match reason {
+        Reason::HandType => format!(
+            "{} outranks {}: {} beats {}",
+            describe(winner),
+            describe(loser),
+            winner.hand_type,
+            loser.hand_type
+        ),
+        Reason::Card(i) => {
+            let mut reason = format!(
+                "both are {}, so it comes down to the cards in order, and card {} is {} against {}",
+                winner.hand_type,
+                i + 1,
+                winner.cards[i].face,
+                loser.cards[i].face
+            );
+            let card = loser.cards[i];
+            if ruleset.is_wild(&card) {
+                let faces: Vec<char> = ruleset.cards().map(|card| card.face).collect();
+                // A losing card is never the strongest, so there's always one above it
+                match card.strength as usize {
+                    0 => write!(
+                        reason,
+                        " ({} is wild but ranks below every other card on its own)",
+                        card.face
+                    ),
+                    strength => write!(
+                        reason,
+                        " ({} is wild but on its own ranks between {} and {})",
+                        card.face,
+                        faces[strength - 1],
+                        faces[strength + 1]
+                    ),
+                }
+                .unwrap();
+            }
+            format!(
+                "{} outranks {}: {}",
+                describe(winner),
+                describe(loser),
+                reason
+            )
+        }
+        Reason::Identical => format!(
+            "{} and {} are the same hand, so they tie",
+            faces(&a.cards),
+            faces(&b.cards)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_hands;
+
+    fn hand(faces: &str, ruleset: &Ruleset) -> Hand {
+        Hand::new(ruleset.parse_cards(faces).unwrap(), 0, ruleset)
+    }
+
+    #[test]
+    fn test_ranking() {
+        let test = include_str!("../test.txt");
+        let jokers = Ruleset::jokers();
+        let rows = ranking(parse_hands(test, &jokers), &jokers).unwrap();
+        assert_eq!(
+            rows[4],
+            Row {
+                rank: 5,
+                cards: "KTJJT".to_string(),
+                hand_type: HandType::new(&[4, 1]),
+                effective: "KTTTT".to_string(),
+                bid: 220,
+                winnings: 1100,
+            }
+        );
+        let table = to_table(&rows);
+        assert!(table.starts_with("rank  hand   type            effective  bid  winnings\n"));
+        assert!(table.contains("   5  KTJJT  Four of a kind  KTTTT      220      1100\n"));
+        assert!(table.ends_with("total winnings: 5905\n"));
+    }
+
+    #[test]
+    fn test_explain() {
+        let jokers = Ruleset::jokers();
+        let (a, b) = (hand("KTJJT", &jokers), hand("KK677", &jokers));
+        assert_eq!(verdict(&a, &b), (Ordering::Greater, Reason::HandType));
+        assert_eq!(
+            explain(&b, &a, &jokers),
+            "KTJJT (Four of a kind, played as KTTTT) outranks KK677 (Two pairs): \
+             Four of a kind beats Two pairs"
+        );
+
+        let (a, b) = (hand("QQQJA", &jokers), hand("JKKK2", &jokers));
+        assert_eq!(verdict(&a, &b), (Ordering::Greater, Reason::Card(0)));
+        assert!(explain(&a, &b, &jokers).ends_with(
+            "card 1 is Q against J (J is wild but ranks below every other card on its own)"
+        ));
+
+        // A wild card that isn't the weakest keeps its place in the order
+        let kings = Ruleset::from_config("order = 23456789TJQKA\nwild = K").unwrap();
+        let (a, b) = (hand("KA234", &kings), hand("AK234", &kings));
+        assert_eq!(verdict(&a, &b), (Ordering::Less, Reason::Card(0)));
+        assert!(explain(&a, &b, &kings)
+            .ends_with("card 1 is A against K (K is wild but on its own ranks between Q and A)"));
+
+        let a = hand("T55J5", &jokers);
+        assert_eq!(verdict(&a, &a), (Ordering::Equal, Reason::Identical));
+
+        // The verdict always agrees with the ranking keys
+        let standard = Ruleset::standard();
+        let hands = parse_hands(include_str!("../input.txt"), &standard);
+        for pair in hands.windows(2) {
+            assert_eq!(verdict(&pair[0], &pair[1]).0, pair[0].key.cmp(&pair[1].key));
+        }
+    }
+}
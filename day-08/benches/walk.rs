@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+use day_08::network::Network;
+use day_08::{parse_map, Map};
+
+fn time<T>(name: &str, f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    println!("{:>24}: {:?}", name, elapsed);
+    (result, elapsed)
+}
+
+// How walking used to work: look each node up by name and clone the next one
+fn walk_cloning(map: &Map, start: &str, steps: u64) -> String {
+    let mut location = start.to_string();
+    let mut it = map.steps.iter().cycle();
+    for _ in 0..steps {
+        let entry = &map.nodes[&location];
+        location = entry.successors[map.instruction(*it.next().unwrap())].clone();
+    }
+    location
+}
+
+fn walk_names<'a>(map: &'a Map, start: &'a str, steps: u64) -> &'a str {
+    let mut location = start;
+    let mut it = map.steps.iter().cycle();
+    for _ in 0..steps {
+        location = map.next(location, *it.next().unwrap());
+    }
+    location
+}
+
+// Walks from AAA for a long way by name and by node index, and checks they end up in the
+// same place. STEPS sets how far:
+//
+//     STEPS=100000000 cargo bench --bench walk
+fn main() {
+    let steps = std::env::var("STEPS")
+        .map(|n| n.parse().expect("STEPS must be a number"))
+        .unwrap_or(20_000_000);
+    let (_, map) = parse_map(include_str!("../input.txt")).unwrap();
+    println!("{} steps", steps);
+
+    let (cloned, cloning_time) = time("names, cloning", || walk_cloning(&map, "AAA", steps));
+    let (named, names_time) = time("names", || walk_names(&map, "AAA", steps));
+    let (network, compile_time) = time("compile", || Network::compile(&map));
+    let start = network.node("AAA").unwrap();
+    let (node, walk_time) = time("indices", || network.walk(start, steps));
+
+    assert_eq!(cloned, named);
+    assert_eq!(network.name(node), named);
+    println!("{:>24}: {}", "all end at", named);
+    let indices_time = walk_time + compile_time;
+    println!(
+        "{:>24}: {:.1}x over cloning, {:.1}x over names",
+        "speedup",
+        cloning_time.as_secs_f64() / indices_time.as_secs_f64(),
+        names_time.as_secs_f64() / indices_time.as_secs_f64()
+    );
+}
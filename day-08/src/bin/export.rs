@@ -0,0 +1,43 @@
+use day_08::export::{to_dot, to_graphml, Path};
+use day_08::network::Network;
+use day_08::parse_map;
+
+const USAGE: &str = "Usage: export <dot|graphml> [start steps [map file]]";
+
+// Usage: export <dot|graphml> [start steps [map file]]
+// Prints the network as Graphviz DOT or GraphML. With a start node and a number of steps,
+// also marks the path taken from there.
+//
+//     export dot 11A 20 test2.txt | dot -Tsvg > network.svg
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let format = args.first().expect(USAGE);
+    let input = match args.get(3) {
+        Some(path) => std::fs::read_to_string(path).expect("Couldn't read map"),
+        None => include_str!("../../input.txt").to_string(),
+    };
+    let (_, map) = parse_map(&input).unwrap();
+    let network = Network::compile(&map);
+
+    let path = match (args.get(1), args.get(2)) {
+        (Some(start), Some(steps)) => {
+            let start = match network.node(start) {
+                Some(node) => node,
+                None => {
+                    eprintln!("No node called {}", start);
+                    std::process::exit(1);
+                }
+            };
+            let steps = steps.parse().expect("Steps must be a number");
+            Some(Path::walk(&network, start, steps))
+        }
+        (None, None) => None,
+        _ => panic!("{}", USAGE),
+    };
+
+    match format.as_str() {
+        "dot" => print!("{}", to_dot(&network, path.as_ref())),
+        "graphml" => print!("{}", to_graphml(&network, path.as_ref())),
+        _ => panic!("{}", USAGE),
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::network::Network;
+
+/// Every step at which one ghost stands on a node ending in `Z`.
+///
+/// A ghost's state is its node and where it is in the instructions, so after at most
+/// `nodes * instructions` steps it's in a state it has been in before and loops from then on.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Ghost {
+    /// Hits before the loop starts, which only happen once
+    pub transient: Vec<u64>,
+    /// The step the loop starts at
+    pub offset: u64,
+    /// How many steps one time round the loop takes
+    pub period: u64,
+    /// Hits in the first time round the loop, from `offset` up to `offset + period`. Each
+    /// repeats every `period` steps.
+    pub hits: Vec<u64>,
+}
+
+impl Ghost {
+    /// Walks from `start` until the ghost's state repeats.
+    pub fn trace(start: usize, network: &Network) -> Ghost {
+        // The step each (node, instruction) state was first seen at, node by node
+        let instructions = network.instructions.len();
+        let mut seen = vec![u64::MAX; network.len() * instructions];
+        let mut ends = Vec::new();
+        let mut node = start;
+        let mut step = 0u64;
+        loop {
+            let state = node * instructions + (step % instructions as u64) as usize;
+            let offset = seen[state];
+            if offset != u64::MAX {
+                let (transient, hits) = ends.into_iter().partition(|&hit| hit < offset);
+                return Ghost {
+                    transient,
+                    offset,
+                    period: step - offset,
+                    hits,
+                };
+            }
+            seen[state] = step;
+            if network.is_end(node) {
+                ends.push(step);
+            }
+
+            node = network.next(node, step);
+            step += 1;
+        }
+    }
+
+    pub fn is_on_end(&self, step: u64) -> bool {
+        if step < self.offset {
+            return self.transient.contains(&step);
+        }
+        let into_loop = (step - self.offset) % self.period;
+        self.hits.iter().any(|&hit| hit - self.offset == into_loop)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// The ghosts are never all on an end at once
+    NoCommonStep,
+    /// The answer, or a number needed on the way to it, doesn't fit in a `u128`
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NoCommonStep => write!(f, "the ghosts are never all on an end at once"),
+            SolveError::Overflow => write!(f, "the answer is too big to work out"),
+        }
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The least common multiple of all the `values`, or [`SolveError::Overflow`] if it doesn't
+/// fit in a `u128`. It's 1 for no values, and 0 if any of them is 0.
+pub fn lcm(values: impl IntoIterator<Item = u128>) -> Result<u128, SolveError> {
+    values.into_iter().try_fold(1u128, |lcm, value| {
+        if lcm == 0 || value == 0 {
+            return Ok(0);
+        }
+        (lcm / gcd(lcm, value))
+            .checked_mul(value)
+            .ok_or(SolveError::Overflow)
+    })
+}
+
+/// `x` with `a * x = 1 (mod m)`, for `a` and `m` with no common factor.
+fn inverse(a: u128, m: u128) -> u128 {
+    // Extended Euclid, keeping the coefficients of `a` reduced mod `m`
+    let (mut r0, mut r1) = (m, a % m);
+    let (mut t0, mut t1) = (0u128, 1u128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        // t0 - q * t1, mod m
+        let qt = mul_mod(q % m, t1, m);
+        (t0, t1) = (t1, (t0 + m - qt) % m);
+    }
+    t0 % m
+}
+
+/// `a * b mod m` without overflowing, for `a` and `b` below `m`.
+fn mul_mod(mut a: u128, mut b: u128, m: u128) -> u128 {
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// Solves `x = a (mod m)` and `x = b (mod n)` together, giving `x` and the modulus it
+/// repeats with, or `None` if no `x` works. The moduli don't need to be coprime.
+pub fn crt(a: u128, m: u128, b: u128, n: u128) -> Result<Option<(u128, u128)>, SolveError> {
+    let g = gcd(m, n);
+    let (a, b) = (a % m, b % n);
+    let difference = add_mod(b, (n - a % n) % n, n);
+    if !difference.is_multiple_of(g) {
+        return Ok(None);
+    }
+
+    // x = a + m * k, where m * k = b - a (mod n), so k = (b - a) / g * (m / g)^-1 (mod n / g)
+    let n_g = n / g;
+    let k = mul_mod((difference / g) % n_g, inverse((m / g) % n_g, n_g), n_g);
+    let lcm = (m / g).checked_mul(n).ok_or(SolveError::Overflow)?;
+    let x = m
+        .checked_mul(k)
+        .and_then(|mk| mk.checked_add(a))
+        .ok_or(SolveError::Overflow)?;
+    Ok(Some((x % lcm, lcm)))
+}
+
+/// The first step at which every ghost is on a node ending in `Z` at the same time.
+pub fn first_common_end(ghosts: &[Ghost]) -> Result<u64, SolveError> {
+    // Until the slowest ghost reaches its loop it can only be on an end at one of its
+    // transient hits, so those are the only early steps worth checking
+    let Some(slowest) = ghosts.iter().max_by_key(|ghost| ghost.offset) else {
+        return Ok(0);
+    };
+    if let Some(&step) = slowest
+        .transient
+        .iter()
+        .find(|&&step| ghosts.iter().all(|ghost| ghost.is_on_end(step)))
+    {
+        return Ok(step);
+    }
+
+    // From then on every ghost is looping, so a step works when it lands on one of the hits
+    // of each ghost's loop. Keep every residue that does so far, modulo all the periods.
+    let mut residues: BTreeSet<u128> = BTreeSet::from([0]);
+    let mut modulus = 1u128;
+    for ghost in ghosts {
+        let period = ghost.period as u128;
+        let mut combined = BTreeSet::new();
+        let mut combined_modulus = modulus;
+        for &residue in &residues {
+            for &hit in &ghost.hits {
+                if let Some((x, lcm)) = crt(residue, modulus, hit as u128, period)? {
+                    combined.insert(x);
+                    combined_modulus = lcm;
+                }
+            }
+        }
+        residues = combined;
+        modulus = combined_modulus;
+        if residues.is_empty() {
+            return Err(SolveError::NoCommonStep);
+        }
+    }
+
+    // The smallest step at or after `slowest.offset` in any of the residue classes. A class
+    // whose first such step overflows can't be the smallest unless they all do.
+    let start = slowest.offset as u128;
+    let step = residues
+        .iter()
+        .filter_map(|&residue| {
+            if residue >= start {
+                return Some(residue);
+            }
+            let laps = (start - residue).div_ceil(modulus);
+            laps.checked_mul(modulus)?.checked_add(residue)
+        })
+        .min()
+        .ok_or(SolveError::Overflow)?;
+    u64::try_from(step).map_err(|_| SolveError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_map;
+
+    fn ghosts(input: &str) -> Vec<Ghost> {
+        let (_, map) = parse_map(input).unwrap();
+        let network = Network::compile(&map);
+        network
+            .ghost_starts()
+            .into_iter()
+            .map(|start| Ghost::trace(start, &network))
+            .collect()
+    }
+
+    // Walks every ghost one step at a time until they're all on an end
+    fn brute_force(input: &str, limit: u64) -> Option<u64> {
+        let (_, map) = parse_map(input).unwrap();
+        let network = Network::compile(&map);
+        let mut nodes = network.ghost_starts();
+        for step in 0..limit {
+            if nodes.iter().all(|&node| network.is_end(node)) {
+                return Some(step);
+            }
+            for node in nodes.iter_mut() {
+                *node = network.next(*node, step);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(2, 3, 3, 5), Ok(Some((8, 15))));
+        // Not coprime: x = 1 (mod 4) and x = 3 (mod 6) gives 9 (mod 12)
+        assert_eq!(crt(1, 4, 3, 6), Ok(Some((9, 12))));
+        assert_eq!(crt(0, 4, 1, 6), Ok(None));
+        assert_eq!(crt(0, 1, 5, 7), Ok(Some((5, 7))));
+        assert_eq!(
+            crt(0, u128::MAX / 2, 1, u128::MAX / 2 - 2),
+            Err(SolveError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm([4, 6]), Ok(12));
+        assert_eq!(lcm([]), Ok(1));
+        assert_eq!(lcm([7, 0, 3]), Ok(0));
+        // Shared factors only count once, however many ghosts share them
+        assert_eq!(lcm(std::iter::repeat_n(2 * 3 * 5 * 7, 100_000)), Ok(210));
+        assert_eq!(
+            lcm((1..=50).map(|n| n * 1009)),
+            Ok(3099044504245996706400 * 1009)
+        );
+        // The primes up to 103 multiply to more than a u128 holds
+        let primes = (2..=103u128).filter(|&n| (2..n).all(|d| !n.is_multiple_of(d)));
+        assert_eq!(lcm(primes), Err(SolveError::Overflow));
+        assert_eq!(lcm([u128::MAX, u128::MAX]), Ok(u128::MAX));
+    }
+
+    #[test]
+    fn test_trace() {
+        let test = include_str!("../test3.txt");
+        let ghosts = ghosts(test);
+        // 11A -> 11Z -> 11B -> 11C -> 11Z
+        assert_eq!(
+            ghosts[0],
+            Ghost {
+                transient: vec![],
+                offset: 1,
+                period: 3,
+                hits: vec![1],
+            }
+        );
+        // 33A -> 3AZ -> 3BZ -> 33C -> 3AZ, two ends in one loop
+        assert_eq!(ghosts[2].hits, vec![1, 2]);
+        // 44A -> 44Z -> 44B -> 44B, one end before it settles
+        assert_eq!(ghosts[3].transient, vec![1]);
+        assert!(ghosts[3].hits.is_empty());
+    }
+
+    #[test]
+    fn test_first_common_end() {
+        let test = include_str!("../test2.txt");
+        assert_eq!(first_common_end(&ghosts(test)), Ok(6));
+
+        // The period of the first ghost isn't the step it first reaches an end, so the
+        // least common multiple of those would say 2
+        let test = include_str!("../test3.txt");
+        let without_44 = &ghosts(test)[..3];
+        assert_eq!(first_common_end(without_44), Ok(4));
+        assert_eq!(brute_force(&test.replace("44A", "44X"), 100), Some(4));
+        // Ghost 44 only ever stands on an end at step 1
+        assert_eq!(
+            first_common_end(&ghosts(test)),
+            Err(SolveError::NoCommonStep)
+        );
+
+        let test = include_str!("../test4.txt");
+        assert_eq!(
+            first_common_end(&ghosts(test)),
+            Err(SolveError::NoCommonStep)
+        );
+        assert_eq!(brute_force(test, 1000), None);
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt::Write;
+
+use crate::network::Network;
+
+const START_COLOR: &str = "palegreen";
+const END_COLOR: &str = "lightcoral";
+const PATH_COLOR: &str = "blue";
+
+/// The edges one ghost takes in its first few steps, and how often it takes each.
+#[derive(Debug)]
+pub struct Path {
+    pub start: usize,
+    pub steps: u64,
+    // Indexed by node, then instruction
+    traversals: Vec<u64>,
+    ways: usize,
+}
+
+impl Path {
+    pub fn walk(network: &Network, start: usize, steps: u64) -> Path {
+        let ways = network.ways();
+        let mut traversals = vec![0; network.len() * ways];
+        let mut node = start;
+        for step in 0..steps {
+            let instruction =
+                network.instructions[(step % network.instructions.len() as u64) as usize];
+            traversals[node * ways + instruction] += 1;
+            node = network.successors(node)[instruction];
+        }
+        Path {
+            start,
+            steps,
+            traversals,
+            ways,
+        }
+    }
+
+    /// How many times the path follows `instruction` out of `node`.
+    pub fn traversals(&self, node: usize, instruction: usize) -> u64 {
+        self.traversals[node * self.ways + instruction]
+    }
+}
+
+/// The edges out of `node`, with every instruction that leads to the same place on one
+/// edge. Each edge is its target and the instructions that take it.
+fn edges(network: &Network, node: usize) -> Vec<(usize, Vec<usize>)> {
+    let mut edges: Vec<(usize, Vec<usize>)> = Vec::new();
+    for (instruction, &target) in network.successors(node).iter().enumerate() {
+        match edges.iter_mut().find(|(t, _)| *t == target) {
+            Some((_, instructions)) => instructions.push(instruction),
+            None => edges.push((target, vec![instruction])),
+        }
+    }
+    edges
+}
+
+fn kind(network: &Network, node: usize) -> &'static str {
+    let name = network.name(node);
+    if name.ends_with('A') {
+        "start"
+    } else if network.is_end(node) {
+        "end"
+    } else {
+        "node"
+    }
+}
+
+/// The network as a Graphviz digraph. Starts and ends are filled in, and with a `path` the
+/// edges it takes are drawn thicker and labelled with how many times they're taken.
+pub fn to_dot(network: &Network, path: Option<&Path>) -> String {
+    let mut dot = String::new();
+    writeln!(dot, "digraph network {{").unwrap();
+    if let Some(path) = path {
+        writeln!(
+            dot,
+            "  label=\"{} steps from {}\";",
+            path.steps,
+            network.name(path.start)
+        )
+        .unwrap();
+    }
+
+    for node in 0..network.len() {
+        let style = match kind(network, node) {
+            "start" => format!(" style=filled fillcolor={}", START_COLOR),
+            "end" => format!(" style=filled fillcolor={}", END_COLOR),
+            _ => String::new(),
+        };
+        writeln!(
+            dot,
+            "  \"{}\" [{}];",
+            network.name(node),
+            style.trim_start()
+        )
+        .unwrap();
+    }
+
+    for node in 0..network.len() {
+        for (target, instructions) in edges(network, node) {
+            let label: String = instructions
+                .iter()
+                .map(|&i| network.instruction_name(i))
+                .collect();
+            let taken: u64 = path.map_or(0, |path| {
+                instructions.iter().map(|&i| path.traversals(node, i)).sum()
+            });
+            let style = if taken > 0 {
+                format!(
+                    " label=\"{} ×{}\" color={} penwidth=3",
+                    label, taken, PATH_COLOR
+                )
+            } else {
+                format!(" label=\"{}\"", label)
+            };
+            writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [{}];",
+                network.name(node),
+                network.name(target),
+                style.trim_start()
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(dot, "}}").unwrap();
+    dot
+}
+
+/// The network as GraphML. Nodes have a `kind` (`start`, `end` or `node`) and a `color`;
+/// edges have the `instructions` that take them and, with a `path`, how many `traversals`
+/// it makes of them.
+pub fn to_graphml(network: &Network, path: Option<&Path>) -> String {
+    let mut xml = String::new();
+    writeln!(xml, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+    writeln!(
+        xml,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )
+    .unwrap();
+    writeln!(
+        xml,
+        "  <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>"
+    )
+    .unwrap();
+    writeln!(
+        xml,
+        "  <key id=\"color\" for=\"node\" attr.name=\"color\" attr.type=\"string\"/>"
+    )
+    .unwrap();
+    writeln!(
+        xml,
+        "  <key id=\"instructions\" for=\"edge\" attr.name=\"instructions\" attr.type=\"string\"/>"
+    )
+    .unwrap();
+    writeln!(
+        xml,
+        "  <key id=\"traversals\" for=\"edge\" attr.name=\"traversals\" attr.type=\"long\">"
+    )
+    .unwrap();
+    writeln!(xml, "    <default>0</default>").unwrap();
+    writeln!(xml, "  </key>").unwrap();
+    writeln!(xml, "  <graph id=\"network\" edgedefault=\"directed\">").unwrap();
+
+    for node in 0..network.len() {
+        let kind = kind(network, node);
+        writeln!(xml, "    <node id=\"{}\">", network.name(node)).unwrap();
+        writeln!(xml, "      <data key=\"kind\">{}</data>", kind).unwrap();
+        let color = match kind {
+            "start" => START_COLOR,
+            "end" => END_COLOR,
+            _ => "white",
+        };
+        writeln!(xml, "      <data key=\"color\">{}</data>", color).unwrap();
+        writeln!(xml, "    </node>").unwrap();
+    }
+
+    for node in 0..network.len() {
+        for (target, instructions) in edges(network, node) {
+            let label: String = instructions
+                .iter()
+                .map(|&i| network.instruction_name(i))
+                .collect();
+            writeln!(
+                xml,
+                "    <edge source=\"{}\" target=\"{}\">",
+                network.name(node),
+                network.name(target)
+            )
+            .unwrap();
+            writeln!(xml, "      <data key=\"instructions\">{}</data>", label).unwrap();
+            if let Some(path) = path {
+                let taken: u64 = instructions.iter().map(|&i| path.traversals(node, i)).sum();
+                if taken > 0 {
+                    writeln!(xml, "      <data key=\"traversals\">{}</data>", taken).unwrap();
+                }
+            }
+            writeln!(xml, "    </edge>").unwrap();
+        }
+    }
+
+    writeln!(xml, "  </graph>").unwrap();
+    writeln!(xml, "</graphml>").unwrap();
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_map;
+
+    fn network(input: &str) -> Network {
+        let (_, map) = parse_map(input).unwrap();
+        Network::compile(&map)
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let network = network(include_str!("../test2.txt"));
+        let dot = to_dot(&network, None);
+        assert!(dot.contains("  \"11A\" [style=filled fillcolor=palegreen];\n"));
+        assert!(dot.contains("  \"22Z\" [style=filled fillcolor=lightcoral];\n"));
+        assert!(dot.contains("  \"22B\" [];\n"));
+        // Both instructions lead from 22B to 22C
+        assert!(dot.contains("  \"22B\" -> \"22C\" [label=\"LR\"];\n"));
+
+        // 11A -L-> 11B -R-> 11Z -L-> 11B -R-> 11Z
+        let path = Path::walk(&network, network.node("11A").unwrap(), 4);
+        let dot = to_dot(&network, Some(&path));
+        assert!(dot.contains("  label=\"4 steps from 11A\";\n"));
+        assert!(dot.contains("  \"11A\" -> \"11B\" [label=\"L ×1\" color=blue penwidth=3];\n"));
+        assert!(dot.contains("  \"11B\" -> \"11Z\" [label=\"R ×2\" color=blue penwidth=3];\n"));
+        assert!(dot.contains("  \"11B\" -> \"XXX\" [label=\"L\"];\n"));
+    }
+
+    #[test]
+    fn test_to_graphml() {
+        let network = network(include_str!("../test2.txt"));
+        let path = Path::walk(&network, network.node("11A").unwrap(), 4);
+        let xml = to_graphml(&network, Some(&path));
+        assert!(xml.contains(
+            "    <node id=\"11A\">\n      <data key=\"kind\">start</data>\n      <data key=\"color\">palegreen</data>\n"
+        ));
+        assert!(xml.contains(
+            "    <edge source=\"11B\" target=\"11Z\">\n      <data key=\"instructions\">R</data>\n      <data key=\"traversals\">2</data>\n    </edge>\n"
+        ));
+        assert_eq!(xml.matches("<edge ").count(), 12);
+    }
+}
@@ -0,0 +1,137 @@
+pub mod cycles;
+pub mod export;
+pub mod network;
+
+use std::collections::HashMap;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alphanumeric1, newline, satisfy},
+    error::{Error, ErrorKind},
+    multi::{many1, separated_list1},
+    sequence::{delimited, separated_pair},
+    IResult,
+};
+
+#[derive(Debug)]
+pub struct MapEntry {
+    pub id: String,
+    /// Where each letter of the alphabet leads, in alphabet order
+    pub successors: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Map {
+    pub steps: Vec<char>,
+    /// The letters that pick each successor, one per way out of a node
+    pub alphabet: Vec<char>,
+    pub nodes: HashMap<String, MapEntry>,
+}
+
+impl Map {
+    /// Which successor `step` picks.
+    pub fn instruction(&self, step: char) -> usize {
+        self.alphabet
+            .iter()
+            .position(|&letter| letter == step)
+            .expect("unexpected step")
+    }
+
+    /// The node reached from `node` by taking `step`.
+    pub fn next(&self, node: &str, step: char) -> &str {
+        let entry = self.nodes.get(node).unwrap();
+        &entry.successors[self.instruction(step)]
+    }
+}
+
+/// The letters for nodes with `ways` successors, given the instructions that use them. Digits
+/// count from `0`, which works for up to ten ways; otherwise two ways are `L` and `R`, and
+/// three are `L`, `M` and `R`.
+pub fn alphabet(steps: &[char], ways: usize) -> Option<Vec<char>> {
+    if ways <= 10 && steps.iter().all(char::is_ascii_digit) {
+        return Some(('0'..='9').take(ways).collect());
+    }
+    match ways {
+        2 => Some(vec!['L', 'R']),
+        3 => Some(vec!['L', 'M', 'R']),
+        _ => None,
+    }
+}
+
+fn successors(input: &str) -> IResult<&str, Vec<&str>> {
+    delimited(
+        tag("("),
+        separated_list1(tag(", "), alphanumeric1),
+        tag(")"),
+    )(input)
+}
+
+fn map_entry(input: &str) -> IResult<&str, MapEntry> {
+    let (input, (id, successors)) = separated_pair(alphanumeric1, tag(" = "), successors)(input)?;
+
+    Ok((
+        input,
+        MapEntry {
+            id: id.to_string(),
+            successors: successors.into_iter().map(str::to_string).collect(),
+        },
+    ))
+}
+
+/// Parses the instructions and the nodes. Every node needs the same number of successors,
+/// and every instruction has to be a letter of the [`alphabet`] for that many.
+pub fn parse_map(input: &str) -> IResult<&str, Map> {
+    let start = input;
+    let (input, steps) = many1(satisfy(|c| c.is_ascii_alphanumeric()))(input)?;
+    let (input, _) = nom::character::complete::newline(input)?;
+    let (input, _) = nom::character::complete::newline(input)?;
+
+    let (input, map_entries) = separated_list1(newline, map_entry)(input)?;
+
+    let ways = map_entries[0].successors.len();
+    let alphabet = alphabet(&steps, ways)
+        .filter(|alphabet| steps.iter().all(|step| alphabet.contains(step)))
+        .filter(|_| {
+            map_entries
+                .iter()
+                .all(|entry| entry.successors.len() == ways)
+        })
+        .ok_or(nom::Err::Failure(Error::new(start, ErrorKind::Verify)))?;
+
+    let mut nodes: HashMap<String, MapEntry> = HashMap::new();
+    for entry in map_entries {
+        nodes.insert(entry.id.clone(), entry);
+    }
+
+    Ok((
+        input,
+        Map {
+            steps,
+            alphabet,
+            nodes,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_map() {
+        let (_, map) = parse_map(include_str!("../test5.txt")).unwrap();
+        assert_eq!(map.alphabet, vec!['L', 'M', 'R']);
+        assert_eq!(map.next("AAA", 'M'), "22Z");
+
+        let (_, map) = parse_map(include_str!("../test6.txt")).unwrap();
+        assert_eq!(map.alphabet, vec!['0', '1', '2', '3']);
+        assert_eq!(map.next("AAA", '3'), "DDD");
+
+        // Every node needs as many successors as the others
+        assert!(parse_map("LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA, AAA)").is_err());
+        // M only means something with three ways
+        assert!(parse_map("LMR\n\nAAA = (AAA, AAA)").is_err());
+        // Four ways need digits
+        assert!(parse_map("LR\n\nAAA = (AAA, AAA, AAA, AAA)").is_err());
+    }
+}
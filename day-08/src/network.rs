@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use crate::Map;
+
+/// A [`Map`] compiled down to node indices, so walking it is just indexing into arrays.
+/// Names are only kept to look nodes up and print them.
+#[derive(Debug)]
+pub struct Network {
+    /// Each instruction as the successor it picks, by its place in the alphabet
+    pub instructions: Vec<usize>,
+    alphabet: Vec<char>,
+    names: Vec<String>,
+    /// Every node's successors one after another, `alphabet.len()` to a node
+    successors: Vec<usize>,
+    ends: Vec<bool>,
+}
+
+impl Network {
+    /// Numbers the nodes in name order.
+    pub fn compile(map: &Map) -> Network {
+        let mut names: Vec<&String> = map.nodes.keys().collect();
+        names.sort();
+        let index: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+
+        let successors = names
+            .iter()
+            .flat_map(|name| &map.nodes[*name].successors)
+            .map(|successor| index[successor.as_str()])
+            .collect();
+        let instructions = map
+            .steps
+            .iter()
+            .map(|&step| map.instruction(step))
+            .collect();
+
+        Network {
+            instructions,
+            alphabet: map.alphabet.clone(),
+            ends: names.iter().map(|name| name.ends_with('Z')).collect(),
+            names: names.into_iter().cloned().collect(),
+            successors,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn node(&self, name: &str) -> Option<usize> {
+        self.names.binary_search_by(|n| n.as_str().cmp(name)).ok()
+    }
+
+    pub fn name(&self, node: usize) -> &str {
+        &self.names[node]
+    }
+
+    /// Whether the node's name ends in `Z`.
+    pub fn is_end(&self, node: usize) -> bool {
+        self.ends[node]
+    }
+
+    /// Every node whose name ends in `A`, which is where the ghosts start.
+    pub fn ghost_starts(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&node| self.names[node].ends_with('A'))
+            .collect()
+    }
+
+    /// How many successors each node has.
+    pub fn ways(&self) -> usize {
+        self.alphabet.len()
+    }
+
+    /// Where each instruction leads from `node`, in alphabet order.
+    pub fn successors(&self, node: usize) -> &[usize] {
+        let ways = self.ways();
+        &self.successors[node * ways..(node + 1) * ways]
+    }
+
+    /// The letter for an instruction.
+    pub fn instruction_name(&self, instruction: usize) -> char {
+        self.alphabet[instruction]
+    }
+
+    /// The node reached from `node` by the instruction used at `step`.
+    pub fn next(&self, node: usize, step: u64) -> usize {
+        let instruction = self.instructions[(step % self.instructions.len() as u64) as usize];
+        self.successors[node * self.ways() + instruction]
+    }
+
+    /// Where `steps` steps from `start` end up.
+    pub fn walk(&self, start: usize, steps: u64) -> usize {
+        let ways = self.ways();
+        let mut node = start;
+        let mut index = 0;
+        for _ in 0..steps {
+            node = self.successors[node * ways + self.instructions[index]];
+            index += 1;
+            if index == self.instructions.len() {
+                index = 0;
+            }
+        }
+        node
+    }
+
+    /// How many steps it takes to get from `start` to `end`, or `None` if it never does.
+    pub fn steps_between(&self, start: usize, end: usize) -> Option<u64> {
+        // Every (node, instruction) state can only come round once before it loops
+        let limit = (self.len() * self.instructions.len()) as u64;
+        let ways = self.ways();
+        let mut node = start;
+        let mut index = 0;
+        for step in 1..=limit {
+            node = self.successors[node * ways + self.instructions[index]];
+            if node == end {
+                return Some(step);
+            }
+            index += 1;
+            if index == self.instructions.len() {
+                index = 0;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_map;
+
+    #[test]
+    fn test_network() {
+        let test = include_str!("../test1_2.txt");
+        let (_, map) = parse_map(test).unwrap();
+        let network = Network::compile(&map);
+        assert_eq!(network.len(), 3);
+        let (aaa, bbb, zzz) = (
+            network.node("AAA").unwrap(),
+            network.node("BBB").unwrap(),
+            network.node("ZZZ").unwrap(),
+        );
+        assert_eq!(network.name(bbb), "BBB");
+        assert_eq!(network.instructions, vec![0, 0, 1]);
+        assert_eq!(network.walk(aaa, 2), aaa);
+        assert_eq!(network.walk(aaa, 3), bbb);
+        assert_eq!(network.steps_between(aaa, zzz), Some(6));
+        assert_eq!(network.steps_between(zzz, aaa), None);
+        assert!(network.is_end(zzz));
+        assert_eq!(network.ghost_starts(), vec![aaa]);
+        assert_eq!(network.node("CCC"), None);
+    }
+
+    #[test]
+    fn test_network_ways() {
+        let test = include_str!("../test6.txt");
+        let (_, map) = parse_map(test).unwrap();
+        let network = Network::compile(&map);
+        assert_eq!(network.ways(), 4);
+        assert_eq!(network.instructions, vec![0, 3, 2, 1]);
+        let node = |name| network.node(name).unwrap();
+        assert_eq!(
+            network.successors(node("BBB")),
+            [node("CCC"), node("ZZZ"), node("DDD"), node("AAA")]
+        );
+        assert_eq!(network.instruction_name(3), '3');
+        assert_eq!(network.steps_between(node("AAA"), node("ZZZ")), Some(8));
+    }
+}
@@ -0,0 +1,34 @@
+use day_09::{parse_sequences, Polynomial};
+use num_bigint::BigInt;
+
+const USAGE: &str = "Usage: extrapolate <forward|backward|at> <n> [input file]";
+
+// Usage: extrapolate <forward|backward|at> <n> [input file]
+// Prints each sequence's value n steps past its end, n steps before its start, or at index
+// n (counting from 0 at the first value), then the total.
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (mode, n) = match (args.first(), args.get(1)) {
+        (Some(mode), Some(n)) => (mode.as_str(), n),
+        _ => panic!("{}", USAGE),
+    };
+    let input = match args.get(2) {
+        Some(path) => std::fs::read_to_string(path).expect("Couldn't read input"),
+        None => include_str!("../../input.txt").to_string(),
+    };
+    let (_, sequences) = parse_sequences(input.trim_end()).unwrap();
+
+    let mut total = BigInt::ZERO;
+    for sequence in &sequences {
+        let polynomial = Polynomial::fit(sequence);
+        let value = match mode {
+            "forward" => polynomial.forward(n.parse().expect("Steps must be a number")),
+            "backward" => polynomial.backward(n.parse().expect("Steps must be a number")),
+            "at" => polynomial.at(n.parse::<BigInt>().expect("Index must be a number")),
+            _ => panic!("{}", USAGE),
+        };
+        println!("{}", value);
+        total += value;
+    }
+    println!("total: {}", total);
+}
@@ -0,0 +1,115 @@
+use nom::{bytes::complete::tag, character::complete::newline, multi::separated_list1, IResult};
+use num_bigint::BigInt;
+
+fn number_sequence(input: &str) -> IResult<&str, Vec<i64>> {
+    separated_list1(tag(" "), nom::character::complete::i64)(input)
+}
+
+pub fn parse_sequences(input: &str) -> IResult<&str, Vec<Vec<i64>>> {
+    separated_list1(newline, number_sequence)(input)
+}
+
+/// A sequence as the polynomial through its values, so it can be evaluated at any index.
+///
+/// The polynomial is kept in Newton form: the first value of each row of the difference
+/// table, which makes the value at index `n` the sum of `C(n, k)` times the `k`th of them.
+/// That works for negative `n` too, and everything is a [`BigInt`] so far-off indices don't
+/// overflow.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Polynomial {
+    /// How many values it was fitted to
+    len: usize,
+    /// The `k`th differences at index 0, without the zeros at the end
+    coefficients: Vec<BigInt>,
+}
+
+impl Polynomial {
+    /// Fits the values, which are at indices 0, 1, 2 and so on.
+    pub fn fit(sequence: &[i64]) -> Polynomial {
+        let mut row: Vec<BigInt> = sequence.iter().map(|&x| BigInt::from(x)).collect();
+        let mut coefficients = Vec::new();
+        while !row.is_empty() && row.iter().any(|x| *x != BigInt::ZERO) {
+            coefficients.push(row[0].clone());
+            row = row.windows(2).map(|pair| &pair[1] - &pair[0]).collect();
+        }
+        Polynomial {
+            len: sequence.len(),
+            coefficients,
+        }
+    }
+
+    /// The degree of the polynomial, or `None` if every value is 0.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// The value at any index, including before 0 and past the end.
+    pub fn at(&self, index: impl Into<BigInt>) -> BigInt {
+        let n = index.into();
+        // C(n, k), from C(n, k + 1) = C(n, k) * (n - k) / (k + 1), which always divides
+        let mut binomial = BigInt::from(1);
+        let mut value = BigInt::ZERO;
+        for (k, coefficient) in self.coefficients.iter().enumerate() {
+            value += &binomial * coefficient;
+            binomial = binomial * (&n - k) / (k + 1);
+        }
+        value
+    }
+
+    /// The value `steps` past the last one.
+    pub fn forward(&self, steps: u64) -> BigInt {
+        self.at(BigInt::from(self.len) - 1 + steps)
+    }
+
+    /// The value `steps` before the first one.
+    pub fn backward(&self, steps: u64) -> BigInt {
+        self.at(-BigInt::from(steps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polynomials(input: &str) -> Vec<Polynomial> {
+        let (_, sequences) = parse_sequences(input).unwrap();
+        sequences.iter().map(|s| Polynomial::fit(s)).collect()
+    }
+
+    #[test]
+    fn test_fit() {
+        let polynomials = polynomials(include_str!("../test.txt"));
+        let degrees: Vec<_> = polynomials.iter().map(|p| p.degree()).collect();
+        assert_eq!(degrees, vec![Some(1), Some(2), Some(3)]);
+        let next: Vec<_> = polynomials.iter().map(|p| p.forward(1)).collect();
+        assert_eq!(next, [18, 28, 68].map(BigInt::from));
+        let previous: Vec<_> = polynomials.iter().map(|p| p.backward(1)).collect();
+        assert_eq!(previous, [-3, 0, 5].map(BigInt::from));
+
+        assert_eq!(Polynomial::fit(&[0, 0]).degree(), None);
+        assert_eq!(Polynomial::fit(&[0, 0]).forward(5), BigInt::ZERO);
+        assert_eq!(Polynomial::fit(&[7]).backward(3), BigInt::from(7));
+    }
+
+    #[test]
+    fn test_at() {
+        // Differences 3, 0 and 2, so 10 + 3 C(n, 1) + 2 C(n, 3)
+        let cubic = Polynomial::fit(&[10, 13, 16, 21, 30, 45]);
+        for n in -20i64..20 {
+            let expected = 10 + 3 * n + n * (n - 1) * (n - 2) / 3;
+            assert_eq!(cubic.at(n), BigInt::from(expected));
+        }
+        assert_eq!(cubic.forward(3), cubic.at(8));
+        assert_eq!(cubic.backward(3), cubic.at(-3));
+
+        // Far past anything an i64 holds
+        let squares = Polynomial::fit(&[0, 1, 4, 9, 16]);
+        let n = BigInt::from(i64::MAX);
+        assert_eq!(squares.at(n.clone()), &n * &n);
+        assert_eq!(squares.at(-&n), &n * &n);
+        assert_eq!(
+            squares.forward(u64::MAX),
+            BigInt::from(u64::MAX as u128 + 4).pow(2)
+        );
+    }
+}